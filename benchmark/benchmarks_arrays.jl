@@ -231,6 +231,38 @@ if RustCall.is_rust_helpers_available()
     end
 end
 
+# ============================================================================
+# RustVec Bulk Scaling (per-element FFI vs one bulk call)
+# ============================================================================
+
+if RustCall.is_rust_helpers_available()
+    println("\n--- RustVec Scaling (f64, size=100000) ---")
+
+    suite["rustvec_scale"] = BenchmarkGroup()
+
+    try
+        scale_vec = RustVec(rand(100_000))
+        scale_fn = RustCall.safe_dlsym(RustCall.get_rust_helpers_lib(), :rust_vec_scale_f64)
+
+        println("RustVec per-element scaling (getindex/setindex! per element):")
+        suite["rustvec_scale"]["per_element"] = @benchmark begin
+            for i in 1:length($scale_vec)
+                $scale_vec[i] = $scale_vec[i] * 1.0
+            end
+        end
+        display(suite["rustvec_scale"]["per_element"])
+
+        println("\nRustVec bulk scaling (rust_vec_scale_f64):")
+        suite["rustvec_scale"]["bulk"] = @benchmark ccall(
+            $scale_fn, Cvoid, (RustCall.CRustVec, Float64),
+            RustCall.CRustVec($scale_vec.ptr, $scale_vec.len, $scale_vec.cap), 1.0
+        )
+        display(suite["rustvec_scale"]["bulk"])
+    catch e
+        @warn "RustVec scaling tests skipped: $e"
+    end
+end
+
 # ============================================================================
 # RustVec Iteration Performance
 # ============================================================================
@@ -332,6 +364,16 @@ if haskey(suite, "rustvec_indexing")
     end
 end
 
+if haskey(suite, "rustvec_scale")
+    println("\nRustVec Scaling (f64, size=100000):")
+    if haskey(suite["rustvec_scale"], "per_element")
+        println("  Per-element: $(minimum(suite["rustvec_scale"]["per_element"]).time) ns")
+    end
+    if haskey(suite["rustvec_scale"], "bulk")
+        println("  Bulk:        $(minimum(suite["rustvec_scale"]["bulk"]).time) ns")
+    end
+end
+
 if haskey(suite, "array_conversion")
     println("\nArray Conversion:")
     if haskey(suite["array_conversion"], "julia_to_rustvec")
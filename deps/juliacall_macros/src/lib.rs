@@ -60,18 +60,280 @@
 //! ```
 //!
 //! This generates FFI functions like `Point_new`, `Point_free`, getters, and setters.
+//!
+//! ## Enums
+//!
+//! Fieldless enums get a fixed integer representation and conversion functions.
+//! Explicit discriminants are preserved, so the values can match existing C/Julia constants:
+//!
+//! ```rust,ignore
+//! use juliacall_macros::julia;
+//!
+//! #[julia(repr = "i32")]
+//! pub enum Color {
+//!     Red = 1,
+//!     Green = 2,
+//!     Blue = 4,
+//! }
+//! ```
+//!
+//! This generates `Color_to_i32` and `Color_is_valid_i32`.
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    Attribute, FnArg, GenericArgument, Ident, ItemFn, ItemImpl, ItemStruct, Pat, PathArguments,
-    ReturnType, Type, Visibility,
+    Attribute, FnArg, GenericArgument, Ident, ItemConst, ItemEnum, ItemFn, ItemImpl, ItemMod,
+    ItemStatic, ItemStruct, ItemType, LitStr, Pat, PathArguments, ReturnType, Type, Visibility,
 };
 
+/// Options given as arguments to the attribute, e.g. `#[julia(repr = "i32")]`
+#[derive(Clone, Default)]
+struct JuliaOptions {
+    /// Integer representation for enums (`repr = "i32"`)
+    repr: Option<Ident>,
+    /// Null-checked struct getters returning a `COption`-style struct (`safe_access`)
+    safe_access: bool,
+    /// Report `Result` errors through the thread-local errno channel (`errno`)
+    errno: bool,
+    /// Generate `<Struct>_debug_string` without a `#[derive(Debug)]` (`debug`)
+    debug: bool,
+    /// Generate `<Struct>_hash` without a `#[derive(Hash)]` (`hash`)
+    hash: bool,
+    /// Generate `<Struct>_new_default` without a `#[derive(Default)]` (`default`)
+    default: bool,
+    /// Reject struct fields whose layout is not `repr(C)`-safe (`verify_repr_c`)
+    verify_repr_c: bool,
+    /// Assert at compile time that the struct is `Send + Sync` (`thread_safe`)
+    thread_safe: bool,
+    /// Null-check the struct pointer in setters and report success as `bool` (`checked_setters`)
+    checked_setters: bool,
+    /// Debug-assert that all slice parameters have the same length (`checked`)
+    checked: bool,
+    /// Return the value through a caller-allocated `out` pointer (`out_param`)
+    out_param: bool,
+    /// Return each tuple element through its own `out<i>` pointer (`out_params`)
+    out_params: bool,
+    /// Return a `Box<dyn Trait>` as an opaque `*mut c_void` handle (`opaque`)
+    opaque: bool,
+    /// Skip generating `<Struct>_free` for structs Julia never owns (`no_free`)
+    no_free: bool,
+    /// Register boxed pointers so `<Struct>_free` rejects double frees (`track_frees`)
+    track_frees: bool,
+    /// Generate `<Struct>_diff_mask` comparing fields with `PartialEq` (`diff`)
+    diff: bool,
+    /// Thread-local scratch buffer element type and length (`scratch(f64, 1024)`)
+    scratch: Option<(Type, syn::LitInt)>,
+    /// Wrap every `pub` method of an impl block, not only `#[julia]` ones (`all`)
+    all: bool,
+    /// Access `u128`/`i128` fields as two `u64` halves (`split_128`)
+    split_128: bool,
+    /// Name `CResult`/`COption` types after their payload types, shared across functions (`canonical`)
+    canonical: bool,
+    /// Leave an impl method unwrapped, e.g. under `#[julia(all)]` (`skip`)
+    skip: bool,
+    /// Item type of the struct's `Iterator` impl, for `<Struct>_next` (`iterator = "i32"`)
+    iterator: Option<Type>,
+    /// Cargo feature gating every generated item (`cfg = "feature_x"`)
+    cfg_feature: Option<LitStr>,
+    /// ABI of the exported wrappers instead of `"C"` (`abi = "C-unwind"`)
+    abi: Option<LitStr>,
+    /// Exported symbol of a function's wrapper, keeping the Rust name (`link_name = "sym"`)
+    link_name: Option<Ident>,
+    /// Note of a `#[deprecated]` attached to a function's wrapper (`deprecated = "use g"`)
+    deprecated: Option<LitStr>,
+    /// Names of the options as written, to reject those the item kind doesn't support
+    given: Vec<Ident>,
+}
+
+/// Options each item kind supports besides `cfg`
+const FN_OPTIONS: &[&str] = &[
+    "errno",
+    "checked",
+    "out_param",
+    "out_params",
+    "opaque",
+    "scratch",
+    "canonical",
+    "abi",
+    "link_name",
+    "deprecated",
+];
+const STRUCT_OPTIONS: &[&str] = &[
+    "safe_access",
+    "debug",
+    "hash",
+    "default",
+    "verify_repr_c",
+    "thread_safe",
+    "checked_setters",
+    "no_free",
+    "track_frees",
+    "diff",
+    "split_128",
+    "iterator",
+];
+const ENUM_OPTIONS: &[&str] = &["repr"];
+const IMPL_OPTIONS: &[&str] = &["all"];
+const METHOD_OPTIONS: &[&str] = &["skip"];
+
+/// Reject options that `kind` items would silently ignore
+fn check_options(options: &JuliaOptions, kind: &str, supported: &[&str]) -> syn::Result<()> {
+    match options
+        .given
+        .iter()
+        .find(|option| *option != "cfg" && !supported.iter().any(|name| *option == name))
+    {
+        Some(option) => Err(syn::Error::new(
+            option.span(),
+            format!("#[julia({option})] is not supported on {kind}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parse the arguments of a `#[julia(...)]` attribute
+fn parse_julia_options(attr: TokenStream2) -> syn::Result<JuliaOptions> {
+    let mut options = JuliaOptions::default();
+    if attr.is_empty() {
+        return Ok(options);
+    }
+
+    let parser = syn::meta::parser(|meta| {
+        if let Some(ident) = meta.path.get_ident() {
+            options.given.push(ident.clone());
+        }
+        if meta.path.is_ident("repr") {
+            let lit: LitStr = meta.value()?.parse()?;
+            let repr = lit.parse::<Ident>()?;
+            if !is_enum_repr_type(&repr) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "#[julia(repr = ...)] must be a primitive integer type such as \"i32\"",
+                ));
+            }
+            options.repr = Some(repr);
+            Ok(())
+        } else if meta.path.is_ident("safe_access") {
+            options.safe_access = true;
+            Ok(())
+        } else if meta.path.is_ident("errno") {
+            options.errno = true;
+            Ok(())
+        } else if meta.path.is_ident("debug") {
+            options.debug = true;
+            Ok(())
+        } else if meta.path.is_ident("hash") {
+            options.hash = true;
+            Ok(())
+        } else if meta.path.is_ident("default") {
+            options.default = true;
+            Ok(())
+        } else if meta.path.is_ident("verify_repr_c") {
+            options.verify_repr_c = true;
+            Ok(())
+        } else if meta.path.is_ident("thread_safe") {
+            options.thread_safe = true;
+            Ok(())
+        } else if meta.path.is_ident("checked_setters") {
+            options.checked_setters = true;
+            Ok(())
+        } else if meta.path.is_ident("checked") {
+            options.checked = true;
+            Ok(())
+        } else if meta.path.is_ident("out_param") {
+            options.out_param = true;
+            Ok(())
+        } else if meta.path.is_ident("out_params") {
+            options.out_params = true;
+            Ok(())
+        } else if meta.path.is_ident("opaque") {
+            options.opaque = true;
+            Ok(())
+        } else if meta.path.is_ident("no_free") {
+            options.no_free = true;
+            Ok(())
+        } else if meta.path.is_ident("track_frees") {
+            options.track_frees = true;
+            Ok(())
+        } else if meta.path.is_ident("diff") {
+            options.diff = true;
+            Ok(())
+        } else if meta.path.is_ident("all") {
+            options.all = true;
+            Ok(())
+        } else if meta.path.is_ident("split_128") {
+            options.split_128 = true;
+            Ok(())
+        } else if meta.path.is_ident("canonical") {
+            options.canonical = true;
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            options.skip = true;
+            Ok(())
+        } else if meta.path.is_ident("iterator") {
+            let lit: LitStr = meta.value()?.parse()?;
+            let item: Type = lit.parse()?;
+            // Exhaustion zero-fills the COption payload, so the item type needs a zero value
+            if !is_ffi_compatible_type(&item) || lacks_zero_value(&item) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "#[julia(iterator = ...)] must name an FFI-compatible item type with a valid \
+                     all-zero value, such as \"i32\"",
+                ));
+            }
+            options.iterator = Some(item);
+            Ok(())
+        } else if meta.path.is_ident("cfg") {
+            options.cfg_feature = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("abi") {
+            let lit: LitStr = meta.value()?.parse()?;
+            if !matches!(lit.value().as_str(), "C" | "C-unwind" | "system") {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "#[julia(abi = ...)] must be one of \"C\", \"C-unwind\" or \"system\"",
+                ));
+            }
+            options.abi = Some(lit);
+            Ok(())
+        } else if meta.path.is_ident("link_name") {
+            let lit: LitStr = meta.value()?.parse()?;
+            options.link_name = Some(lit.parse::<Ident>()?);
+            Ok(())
+        } else if meta.path.is_ident("deprecated") {
+            options.deprecated = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("scratch") {
+            let content;
+            syn::parenthesized!(content in meta.input);
+            let elem: Type = content.parse()?;
+            content.parse::<syn::Token![,]>()?;
+            let len: syn::LitInt = content.parse()?;
+            len.base10_parse::<usize>()?;
+            options.scratch = Some((elem, len));
+            Ok(())
+        } else {
+            Err(meta.error("unsupported #[julia] option"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    Ok(options)
+}
+
+/// Check if an identifier names an integer type usable as an enum representation
+fn is_enum_repr_type(ident: &Ident) -> bool {
+    matches!(
+        ident.to_string().as_str(),
+        "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "isize" | "usize"
+    )
+}
+
 /// Check if a type is FFI-compatible (primitive types that can be passed through C ABI)
 fn is_ffi_compatible_type(ty: &Type) -> bool {
     match ty {
+        _ if is_f16_type(ty) => true, // Passed as its u16 bits
         Type::Path(type_path) => {
             if let Some(segment) = type_path.path.segments.last() {
                 let type_name = segment.ident.to_string();
@@ -99,10 +361,236 @@ fn is_ffi_compatible_type(ty: &Type) -> bool {
         }
         Type::Tuple(tuple) if tuple.elems.is_empty() => true, // () is FFI-compatible
         Type::Ptr(_) => true,                                 // Raw pointers are FFI-compatible
+        Type::BareFn(bare_fn) => is_c_callable_fn(bare_fn),   // C callbacks
+        _ => false,
+    }
+}
+
+/// Check if a function pointer type uses a C-compatible ABI (`extern "C" fn(i32) -> i32`)
+///
+/// Julia passes such callbacks as `@cfunction` pointers; Rust-ABI `fn` pointers are
+/// not callable from C.
+fn is_c_callable_fn(bare_fn: &syn::TypeBareFn) -> bool {
+    bare_fn.abi.as_ref().is_some_and(|abi| {
+        abi.name
+            .as_ref()
+            .is_none_or(|name| matches!(name.value().as_str(), "C" | "C-unwind" | "system"))
+    })
+}
+
+/// Check if the attributes contain `#[derive(..., <name>, ...)]`
+fn has_derive(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.segments.last().is_some_and(|s| s.ident == name) {
+                    found = true;
+                }
+                Ok(())
+            });
+            found
+        })
+}
+
+/// Check if a type is an array `[T; N]` of a primitive element type and extract T
+fn extract_array_elem_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Array(array)
+            if is_ffi_compatible_type(&array.elem) && !matches!(*array.elem, Type::Ptr(_)) =>
+        {
+            Some(&array.elem)
+        }
+        _ => None,
+    }
+}
+
+/// Check if a type looks like a nested user struct (e.g. another `#[julia]` struct)
+///
+/// Matches plain, non-generic type paths that are neither primitives nor known
+/// library containers, such as `Point` or `geometry::Point`.
+fn is_nested_struct_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            type_path.path.segments.last().is_some_and(|segment| {
+                segment.arguments.is_none()
+                    && segment
+                        .ident
+                        .to_string()
+                        .starts_with(|c: char| c.is_ascii_uppercase())
+            }) && !is_ffi_compatible_type(ty)
+                && !needs_clone_for_getter(ty)
+                && !is_non_ffi_type(ty)
+        }
+        _ => false,
+    }
+}
+
+/// Check if a struct field can cross the C ABI by value as part of its `repr(C)` struct
+///
+/// Accepts primitives other than `char`, raw pointers and arrays of these; nested
+/// `#[julia]` structs are accepted separately, through their `#[julia(nested)]` marking.
+fn is_by_value_field_type(ty: &Type) -> bool {
+    match ty {
+        Type::Array(array) => is_by_value_field_type(&array.elem),
+        Type::Path(type_path) if type_path.path.is_ident("char") => false,
+        _ => is_ffi_compatible_type(ty),
+    }
+}
+
+/// Assert at compile time that a `#[julia(nested)]` field holds a `#[julia]` struct
+///
+/// `#[julia]` structs define `__JULIA_STRUCT`; every other type resolves to the
+/// fallback trait's `false`.
+fn generate_nested_field_guard(
+    struct_name: &Ident,
+    field_label: &str,
+    field_ty: &Type,
+) -> TokenStream2 {
+    use syn::spanned::Spanned;
+    quote_spanned! {field_ty.span()=>
+        const _: () = {
+            trait __JuliaStructFallback {
+                const __JULIA_STRUCT: bool = false;
+            }
+            impl<T: ?Sized> __JuliaStructFallback for T {}
+            assert!(
+                <#field_ty>::__JULIA_STRUCT,
+                concat!(
+                    "field `", #field_label, "` of `", stringify!(#struct_name),
+                    "` is marked #[julia(nested)], but `", stringify!(#field_ty),
+                    "` is not a #[julia] struct"
+                )
+            );
+        };
+    }
+}
+
+/// Extract the integer behind a `NonZeroU32`-style type (or `NonZero<u32>`)
+///
+/// Such values cross the C ABI as the plain integer, so zero can be rejected on the
+/// Rust side instead of becoming an invalid `NonZero*` value.
+fn nonzero_primitive(ty: &Type) -> Option<Ident> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let primitive = match &segment.arguments {
+        PathArguments::None => {
+            let suffix = segment
+                .ident
+                .to_string()
+                .strip_prefix("NonZero")?
+                .to_lowercase();
+            format_ident!("{}", suffix)
+        }
+        PathArguments::AngleBracketed(args) if segment.ident == "NonZero" => {
+            match args.args.first()? {
+                GenericArgument::Type(Type::Path(inner)) => inner.path.get_ident()?.clone(),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    is_enum_repr_type(&primitive).then_some(primitive)
+}
+
+/// Check if a type has no valid all-zero value (`char`, `NonZero*`, fn pointers, references)
+///
+/// Such types cannot be the payload of a generated `CResult`/`COption`, whose unused
+/// variant is zero-filled.
+fn lacks_zero_value(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().is_some_and(|segment| {
+            let type_name = segment.ident.to_string();
+            type_name == "char" || type_name.starts_with("NonZero")
+        }),
+        Type::BareFn(_) | Type::Reference(_) => true,
+        Type::Paren(paren) => lacks_zero_value(&paren.elem),
+        Type::Group(group) => lacks_zero_value(&group.elem),
         _ => false,
     }
 }
 
+/// Reject `Result`/`Option` payloads that would be zero-filled without a valid zero value
+fn check_zero_fillable(func_name: &Ident, payloads: &[&Type]) -> Option<TokenStream2> {
+    let ty = payloads.iter().find(|ty| lacks_zero_value(ty))?;
+    let hint = match nonzero_primitive(ty) {
+        Some(primitive) => format!("Return the underlying `{primitive}` instead (`.get()`)."),
+        None => "Use an integer type instead (e.g. `u32` for `char`).".to_string(),
+    };
+    Some(quote! {
+        compile_error!(concat!(
+            "#[julia] function `", stringify!(#func_name),
+            "` returns `", stringify!(#ty),
+            "` inside Result/Option, but it has no valid all-zero value to fill the unused variant. ",
+            #hint
+        ));
+    })
+}
+
+/// Assert at compile time that user-defined payloads accept an all-zero value
+///
+/// `#[julia]` enums define `__JULIA_ZERO_IS_VALID` (whether 0 is a declared discriminant);
+/// every other type resolves to the fallback trait's `true`.
+fn generate_zero_fill_guard(func_name: &Ident, payloads: &[&Type]) -> TokenStream2 {
+    let checked: Vec<_> = payloads
+        .iter()
+        .filter(|ty| is_nested_struct_type(ty))
+        .collect();
+    if checked.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            trait __JuliaZeroFallback {
+                const __JULIA_ZERO_IS_VALID: bool = true;
+            }
+            impl<T: ?Sized> __JuliaZeroFallback for T {}
+            #(
+                assert!(
+                    <#checked>::__JULIA_ZERO_IS_VALID,
+                    concat!(
+                        "#[julia] function `", stringify!(#func_name),
+                        "` returns the enum `", stringify!(#checked),
+                        "` inside Result/Option, but 0 is not one of its discriminants"
+                    )
+                );
+            )*
+        };
+    }
+}
+
+/// Assert at compile time that stored payloads have a C representation
+///
+/// Zero-sized types (unit structs, `PhantomData`) pass the syntactic checks but cannot be
+/// laid out in a `#[repr(C)]` payload; `()` is exempt because it lowers to no field.
+fn generate_zero_size_guard(func_name: &Ident, payloads: &[&Type]) -> TokenStream2 {
+    let sized: Vec<_> = payloads
+        .iter()
+        .filter(|ty| !matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty()))
+        .collect();
+    if sized.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        const _: () = {
+            #(
+                assert!(
+                    std::mem::size_of::<#sized>() > 0,
+                    concat!(
+                        "#[julia] function `", stringify!(#func_name),
+                        "` returns the zero-sized type `", stringify!(#sized),
+                        "` inside Result/Option, which has no C representation"
+                    )
+                );
+            )*
+        };
+    }
+}
+
 /// Check if a type needs cloning for getter (String, Vec, etc.)
 fn needs_clone_for_getter(ty: &Type) -> bool {
     match ty {
@@ -176,6 +664,96 @@ fn extract_option_type(ty: &Type) -> Option<OptionTypeInfo> {
     }
 }
 
+/// Check if a type is Box<T> for a sized path type T and extract T
+fn extract_box_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident != "Box" {
+                return None;
+            }
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner @ Type::Path(_))) = args.args.first() {
+                    return Some(inner.clone());
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Check if a type is Vec<T> for an FFI-compatible element type T and extract T
+fn extract_vec_type(ty: &Type) -> Option<Type> {
+    extract_vec_elem_type(ty)
+        .filter(|elem| is_ffi_compatible_type(elem))
+        .cloned()
+}
+
+/// Extract the element type T of any Vec<T>
+fn extract_vec_elem_type(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(type_path) => {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident != "Vec" {
+                return None;
+            }
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(elem)) = args.args.first() {
+                    return Some(elem);
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Check if a type is `HashMap<K, V>` with FFI-compatible K and V and extract both
+fn extract_hashmap_types(ty: &Type) -> Option<(Type, Type)> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "HashMap" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let mut types = args.args.iter().filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    });
+    let (key, value) = (types.next()?, types.next()?);
+    (is_ffi_compatible_type(key) && is_ffi_compatible_type(value))
+        .then(|| (key.clone(), value.clone()))
+}
+
+/// Check if a type is `Cow<'static, str>`
+fn is_static_cow_str_type(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(segment) = type_path.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    segment.ident == "Cow"
+        && matches!(
+            args.args.iter().collect::<Vec<_>>().as_slice(),
+            [GenericArgument::Lifetime(lifetime), GenericArgument::Type(Type::Path(elem))]
+                if lifetime.ident == "static" && elem.path.is_ident("str")
+        )
+}
+
+/// Check if a type is `String`
+fn is_string_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("String"))
+}
+
 /// Check if a type is a known non-FFI-compatible type (String, Vec<T>, Box<T>, etc.)
 fn is_non_ffi_type(ty: &Type) -> bool {
     match ty {
@@ -218,6 +796,32 @@ fn generate_c_result_type(func_name: &Ident, ok_type: &Type, err_type: &Type) ->
     }
 }
 
+/// Spell a type as an identifier fragment for canonical type names
+///
+/// `f64` stays `f64`, `*mut Point` becomes `ptr_mut_Point` and `[u8; 4]` becomes `u8_4`.
+fn mangle_type_name(ty: &Type) -> String {
+    let spelled = quote!(#ty).to_string().replace('*', " ptr ");
+    spelled
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Suffix of the canonical `CResult_<ok>_<err>` type for `Result<ok, err>`
+fn canonical_result_suffix(ok_type: &Type, err_type: &Type) -> Ident {
+    format_ident!(
+        "{}_{}",
+        mangle_type_name(ok_type),
+        mangle_type_name(err_type)
+    )
+}
+
+/// Suffix of the canonical `COption_<inner>` type for `Option<inner>`
+fn canonical_option_suffix(inner_type: &Type) -> Ident {
+    format_ident!("{}", mangle_type_name(inner_type))
+}
+
 /// Generate C-compatible Option type definition for a specific T
 fn generate_c_option_type(func_name: &Ident, inner_type: &Type) -> TokenStream2 {
     let option_type_name = format_ident!("COption_{}", func_name);
@@ -231,6 +835,23 @@ fn generate_c_option_type(func_name: &Ident, inner_type: &Type) -> TokenStream2
     }
 }
 
+/// Generate an expression building the `None` value of a C-compatible Option type
+///
+/// The payload is zero-filled so Julia never reads uninitialized memory.
+fn generate_c_option_none(option_type_name: &Ident) -> TokenStream2 {
+    quote! {
+        {
+            let mut opt = std::mem::MaybeUninit::<#option_type_name>::uninit();
+            let ptr = opt.as_mut_ptr();
+            unsafe {
+                std::ptr::addr_of_mut!((*ptr).is_some).write(0);
+                std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).value), 0, 1);
+                opt.assume_init()
+            }
+        }
+    }
+}
+
 /// The `#[julia]` attribute macro for FFI-compatible functions and structs.
 ///
 /// # For Functions
@@ -246,6 +867,79 @@ fn generate_c_option_type(func_name: &Ident, inner_type: &Type) -> TokenStream2
 /// }
 /// ```
 ///
+/// Slice parameters `xs: &[T]` are lowered to `xs_ptr: *const T, xs_len: usize`
+/// (`*mut T` for `&mut [T]`); a zero length yields an empty slice without reading the
+/// pointer. `#[julia(checked)]` debug-asserts that all slice parameters share one length.
+///
+/// Struct references `p: &Point` / `p: &mut Point` are lowered to `*const Point` /
+/// `*mut Point`; passing null panics rather than dereferencing it.
+///
+/// Functions returning `Box<T>` return `*mut T`, handing ownership to the caller (free it
+/// with the `<T>_free` of a `#[julia]` struct).
+///
+/// `#[julia(opaque)]` on a function returning `Box<dyn Trait>` returns the trait object as
+/// an opaque `*mut c_void` handle (boxed twice to make the pointer thin), released with the
+/// generated `<fn>_free`. Julia can only pass the handle back to Rust functions, which cast
+/// it to `*mut Box<dyn Trait>`.
+///
+/// Functions returning `Option<*mut T>` (or `*const T`) return the bare pointer, with
+/// null for `None`.
+///
+/// Functions returning `Result<*mut T, E>` (or `*const T`) return
+/// `CResultPtr_<fn> { is_ok, ptr, err }`, with a null `ptr` on error.
+///
+/// Functions returning a fixed-size array `[T; N]` return `CArr_<fn> { data: [T; N] }`,
+/// since C cannot return arrays by value.
+///
+/// Functions returning `Result<T, String>` return `T` (zeroed on error); the message of
+/// the last error on the current thread is taken with `<fn>_last_error() -> *mut c_char`
+/// (null if none), which the caller frees with `<fn>_last_error_free`.
+///
+/// The unused variant of a returned `Result`/`Option` is zero-filled, so payloads without
+/// a valid zero value (`char`, `NonZero*`, fn pointers, `#[julia]` enums lacking a `0`
+/// discriminant) are rejected at compile time, as are zero-sized payloads other than `()`.
+///
+/// Functions returning `Result<Vec<T>, String>` return `CResult_<fn>` holding a
+/// `CVec_<fn> { ptr, len, cap }` or a `*mut c_char` message; free either arm with
+/// `CResult_<fn>_free`.
+///
+/// `NonZeroU32`-style parameters and returns cross as the plain integer; a zero argument
+/// panics.
+///
+/// `extern "C" fn(...)` parameters (e.g. `cb: extern "C" fn(i32) -> i32`) pass through
+/// unchanged; Julia supplies them with `@cfunction`.
+///
+/// Functions returning `Cow<'static, str>` return `CCowStr_<fn> { ptr, owned }`; pass it to
+/// `CCowStr_<fn>_free`, which only frees `owned = 1` strings.
+///
+/// Functions returning `HashMap<K, V>` of primitive `K`/`V` return `CMap_<fn> { keys, values }`,
+/// two parallel vectors in an unspecified order; free both with `CMap_<fn>_free`.
+///
+/// `#[julia(abi = "C-unwind")]` exports the wrappers with that ABI instead of `"C"`, so a
+/// panic can unwind into the caller; `"C"`, `"C-unwind"` and `"system"` are accepted.
+/// Like other function options, it is rejected on structs and impl blocks.
+///
+/// `#[julia(link_name = "sym")]` exports the wrapper as `sym` (`#[export_name]`) while the
+/// Rust function keeps its name; helpers such as `<fn>_last_error` are not renamed.
+///
+/// `#[julia(deprecated = "use f2 instead")]` marks the exported wrapper `#[deprecated]` with
+/// that note, so Rust callers get a warning; the symbol is exported as before.
+///
+/// `#[julia(canonical)]` uses a `CResult`/`COption` named after its payload types
+/// (`CResult_f64_i32`, `COption_u8_4` for `Option<[u8; 4]>`) instead of the function, so functions with the
+/// same `Result`/`Option` type share one definition. The type is declared once with
+/// `julia_canonical!(Result<f64, i32>)` in a module the functions can see.
+///
+/// `#[julia(out_param)]` turns `fn f(...) -> T` into `fn f(..., out: *mut T)`, writing
+/// the result into caller-allocated memory instead of returning it by value.
+///
+/// `#[julia(out_params)]` turns `fn f(...) -> (A, B)` into `fn f(..., out0: *mut A,
+/// out1: *mut B)`, writing each tuple element through its own pointer (null pointers are
+/// skipped); a `()` return adds no pointers.
+///
+/// `#[julia(scratch(f64, 1024))]` gives the body a `with_scratch(|buf: &mut [f64]| ...)`
+/// helper lending a reusable thread-local buffer of 1024 elements.
+///
 /// # For Structs
 ///
 /// Adds `#[repr(C)]` and generates FFI wrapper functions for construction,
@@ -260,57 +954,628 @@ fn generate_c_option_type(func_name: &Ident, inner_type: &Type) -> TokenStream2
 ///     pub y: f64,
 /// }
 /// ```
+///
+/// `<Struct>_swap(a, b)` exchanges two instances in place (a no-op if either is null).
+///
+/// `<Struct>_box(value)` moves a struct passed by value onto the heap and returns the owning
+/// pointer. It is only generated when every field is a primitive, pointer, array of these or
+/// `#[julia(nested)]` struct, so that the struct itself can be passed through the C ABI.
+///
+/// `#[julia(iterator = "i32")]` on a struct implementing `Iterator<Item = i32>` adds
+/// `<Struct>_next(ptr) -> COption_<Struct>_next`, stepping the iterator lazily; `is_some = 0`
+/// signals exhaustion (or a null pointer). The item type must be given since the macro
+/// cannot see trait impls.
+///
+/// `<Struct>_set_all(ptr, ...)` writes every primitive or pointer field in declaration order
+/// in one call (tuple struct fields are named `f0`, `f1`, ...); other fields are left as is.
+///
+/// `<Struct>_iter_new(base, len)` / `<Struct>_iter_next(iter)` / `<Struct>_iter_free(iter)`
+/// walk a contiguous array of structs; `_iter_next` returns null once exhausted.
+///
+/// When the struct derives `Debug` (or with `#[julia(debug)]`), `<Struct>_debug_string`
+/// returns its `{:?}` formatting as a C string owned by the caller; free it with
+/// `<Struct>_debug_string_free`. Derives are only visible when placed after `#[julia]`.
+///
+/// Likewise, deriving `Hash` (or `#[julia(hash)]`) adds `<Struct>_hash(ptr) -> u64`, computed
+/// with `DefaultHasher` so equal values hash equally (0 for a null pointer).
+///
+/// Deriving `Default` (or `#[julia(default)]`) adds `<Struct>_new_default()`, a zero-argument
+/// constructor boxing `Struct::default()`; free it with `<Struct>_free`.
+///
+/// Every field also gets `<Struct>_field_width_<field>() -> u8`, its size in bytes (saturating
+/// at 255), so Julia can lay out narrow integer fields without guessing their width.
+///
+/// Fields of tuple structs are named by index: `struct Celsius(f64)` gets
+/// `Celsius_get_0(ptr)` and `Celsius_set_0(ptr, value)`.
+///
+/// Array fields `data: [f64; 3]` get `<Struct>_get_data_at(ptr, i)` and
+/// `<Struct>_set_data_at(ptr, i, value)`; out-of-bounds reads return zero and
+/// out-of-bounds writes are ignored. For arrays of fn pointers the getter returns a
+/// nullable `Option<fn>` instead, null when out of bounds.
+///
+/// `Option<T>` fields of a primitive `T` get `<Struct>_get_<field>` returning
+/// `COption_<Struct>_get_<field>` (`is_some = 0` for `None`) and
+/// `<Struct>_set_<field>(ptr, is_some, value)`, which stores `None` when `is_some` is 0.
+///
+/// Fields holding another `#[julia]` struct are marked `#[julia(nested)] center: Point`
+/// and get a getter returning `*const Point` into the parent, valid only while the parent
+/// is alive and unmoved, and a setter taking `*const Point` that clones the value in (the
+/// field type must be `Clone`). Marking a type that is not a `#[julia]` struct is a compile
+/// error; unmarked fields of other types get no accessors.
+///
+/// `<Struct>_free_array(ptrs, len)` frees every non-null pointer of an array in one call
+/// (e.g. the elements of a `Vec<Self>` method result); the array itself is not freed.
+///
+/// `#[julia(no_free)]` omits `<Struct>_free` and `<Struct>_free_array` for structs Julia
+/// never owns (e.g. Rust-side globals), so they can't be freed by mistake; accessors are
+/// still generated.
+///
+/// `#[julia(track_frees)]` registers pointers boxed by `#[julia]` constructors and
+/// methods in a global set; `<Struct>_free` then ignores unregistered or already freed
+/// pointers and counts them in `<Struct>_invalid_free_count()`. Off by default.
+///
+/// `#[julia(diff)]` generates `<Struct>_diff_mask(a, b) -> u64`, setting bit `i` when
+/// field `i` differs by `PartialEq` (at most 64 fields; all bits are set if either is null).
+///
+/// `#[julia(split_128)]` exposes `u128`/`i128` fields as `u64` halves through
+/// `<Struct>_get_<field>_lo`/`_hi` and `<Struct>_set_<field>_lo`/`_hi`, since 128-bit
+/// integers have no reliable C ABI. The single-value accessors are then only generated
+/// with the `i128_abi` feature.
+///
+/// With the `half` feature, `half::f16` fields (and function parameters) cross the ABI as
+/// their `u16` bit pattern: `<Struct>_get_<field>` returns `to_bits()` and the setter takes
+/// bits for `f16::from_bits`.
+///
+/// Raw pointer fields additionally get `<Struct>_get_<field>_checked`, returning a
+/// `COption`-style struct with `is_some = 0` when the field (or the struct pointer) is null.
+///
+/// With `#[julia(safe_access)]`, getters of primitive fields check for a null
+/// pointer and return a `COption_<Struct>_get_<field>` instead of dereferencing.
+/// Callback fields, which have no zero value, return a nullable `Option<extern "C" fn>`.
+///
+/// Setters don't check the struct pointer by default. With `#[julia(checked_setters)]`,
+/// every `<Struct>_set_*` returns `bool`: `false` (and no write) for a null pointer or an
+/// out-of-bounds index, `true` otherwise.
+///
+/// Heap fields (`String`, `Vec`, `Box`, ...) are not `repr(C)`-safe, so such structs
+/// must only cross FFI by pointer; the generated docs list them. `#[julia(verify_repr_c)]`
+/// rejects these fields at compile time instead.
+///
+/// `#[julia(thread_safe)]` asserts at compile time that the struct is `Send + Sync`, for
+/// instances shared across Julia threads.
+///
+/// # For Constants and Statics
+///
+/// `#[julia] pub const MAX_USERS: u32 = 1000;` (or a `static`) keeps the item and adds
+/// `MAX_USERS_value() -> u32`, so Julia can query compile-time configuration. Only primitive
+/// and raw pointer types are accepted; `static mut` is rejected.
+///
+/// # For Type Aliases
+///
+/// `#[julia] type Vec2 = Point;` re-exports the FFI functions of the `#[julia]` struct
+/// `Point` as thin `Vec2_*` wrappers. The alias must appear after the struct, in the
+/// same module or a child module.
+///
+/// # For Enums
+///
+/// Pins the discriminant type (`#[julia(repr = "i32")]`, default `i32`) and generates
+/// `<Enum>_to_<repr>` / `<Enum>_is_valid_<repr>` using the declared discriminant values.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[julia(repr = "u8")]
+/// pub enum Status {
+///     Idle = 0,
+///     Busy = 10,
+/// }
+/// ```
+///
+/// # Conditional Compilation
+///
+/// `#[julia(cfg = "feature_x")]` attaches `#[cfg(feature = "feature_x")]` to the item and
+/// to everything generated for it (wrappers, helper types, accessors, `_free`).
+/// `#[cfg(...)]` attributes on the item itself are copied to the generated items as well.
+///
+/// # For Impl Blocks
+///
+/// Methods marked `#[julia]` get `<Struct>_<method>` wrappers. With `#[julia(all)]` on
+/// the impl block, every `pub` method is wrapped without its own `#[julia]`; private
+/// methods are still skipped unless marked, and `#[julia(skip)]` opts a method out.
+/// Wrapping a method named after a struct helper (`swap`, `free_array`, `iter_new`,
+/// `iter_next`, `iter_free`) is a compile error, since both would export `<Struct>_swap`.
+/// Trait impls (`impl Display for Point`) are rejected; call trait methods from an
+/// inherent method instead.
+/// Impls with generic arguments (`impl Wrapper<i32>`) are rejected as well; write the
+/// impl on a concrete alias (`type WrapperI32 = Wrapper<i32>;`) to get `WrapperI32_*` wrappers.
+///
+/// Static methods returning `Result<Self, E>` (e.g. `fn from_str(n: i32) -> Result<Self, i32>`)
+/// are fallible constructors returning `*mut Struct`, null on `Err` (the error is dropped).
+///
+/// An `Option<Self>` return becomes a nullable `*mut Struct`: boxed for `Some`, null for
+/// `None`.
+///
+/// A `Vec<T>` return becomes `CVec_<Struct>_<method> { ptr, len, cap }`, which the caller
+/// frees with the generated `CVec_<Struct>_<method>_free`. For `Vec<Self>` the buffer holds
+/// one boxed `*mut Struct` (as `*mut c_void`) per element: free them with
+/// `<Struct>_free_array` (or each with `<Struct>_free`), then the buffer with
+/// `CVec_<Struct>_<method>_free`.
+///
+/// # For Modules
+///
+/// `#[julia]` on an inline `mod name { ... }` applies itself (with the same options) to
+/// each `pub` function, struct and enum, each inherent impl block and each inline submodule;
+/// each item uses the options that apply to its kind. Private items, trait impls and items
+/// with their own `#[julia]` are left as they are.
 #[proc_macro_attribute]
-pub fn julia(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn julia(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options = match parse_julia_options(attr.into()) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let expanded = expand_julia_item(item.into(), &options);
+    match &options.cfg_feature {
+        Some(feature) => apply_cfg_feature(expanded, feature),
+        None => expanded,
+    }
+    .into()
+}
+
+/// Expand the item under a `#[julia]` attribute according to its kind
+fn expand_julia_item(item: TokenStream2, options: &JuliaOptions) -> TokenStream2 {
     // Try to parse as a function first
-    if let Ok(func) = syn::parse::<ItemFn>(item.clone()) {
-        return transform_function(func).into();
+    if let Ok(func) = syn::parse2::<ItemFn>(item.clone()) {
+        if let Err(error) = check_options(options, "functions", FN_OPTIONS) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&func.attrs);
+        let symbol = options.link_name.as_ref().unwrap_or(&func.sig.ident);
+        let guard = generate_symbol_guard(symbol, &func.attrs);
+        let func_name = func.sig.ident.clone();
+        let mut expanded = transform_function(func, options);
+        if let Some(abi) = &options.abi {
+            expanded = apply_abi(expanded, abi);
+        }
+        if let Some(link_name) = &options.link_name {
+            expanded = apply_link_name(expanded, &func_name, link_name);
+        }
+        if let Some(note) = &options.deprecated {
+            expanded = apply_deprecated(expanded, &func_name, note);
+        }
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
     }
 
     // Try to parse as a struct
-    if let Ok(item_struct) = syn::parse::<ItemStruct>(item.clone()) {
-        return transform_struct(item_struct).into();
+    if let Ok(item_struct) = syn::parse2::<ItemStruct>(item.clone()) {
+        if let Err(error) = check_options(options, "structs", STRUCT_OPTIONS) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&item_struct.attrs);
+        let guard = generate_symbol_guard(&item_struct.ident, &item_struct.attrs);
+        let expanded = transform_struct(item_struct, options);
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
     }
 
-    // Try to parse as an impl block
-    if let Ok(item_impl) = syn::parse::<ItemImpl>(item.clone()) {
-        return transform_impl(item_impl).into();
+    // Try to parse as an enum
+    if let Ok(item_enum) = syn::parse2::<ItemEnum>(item.clone()) {
+        if let Err(error) = check_options(options, "enums", ENUM_OPTIONS) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&item_enum.attrs);
+        let guard = generate_symbol_guard(&item_enum.ident, &item_enum.attrs);
+        let expanded = transform_enum(item_enum, options);
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
     }
 
-    // If nothing matches, return an error
-    let item2: TokenStream2 = item.into();
-    quote! {
-        compile_error!("#[julia] can only be applied to functions, structs, or impl blocks");
-        #item2
+    // Try to parse as a type alias
+    if let Ok(item_type) = syn::parse2::<ItemType>(item.clone()) {
+        if let Err(error) = check_options(options, "type aliases", &[]) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&item_type.attrs);
+        let guard = generate_symbol_guard(&item_type.ident, &item_type.attrs);
+        let expanded = transform_type_alias(item_type);
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
     }
-    .into()
-}
 
-/// Transform a function with #[julia] attribute to FFI-compatible form
-fn transform_function(func: ItemFn) -> TokenStream2 {
-    // Check for unsafe functions
-    if func.sig.unsafety.is_some() {
-        return quote! {
-            compile_error!("#[julia] cannot be applied to unsafe functions directly. The function will be made extern \"C\" which has its own safety semantics.");
+    // Try to parse as a constant or static
+    if let Ok(item_const) = syn::parse2::<ItemConst>(item.clone()) {
+        if let Err(error) = check_options(options, "constants", &[]) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&item_const.attrs);
+        let guard = generate_symbol_guard(&item_const.ident, &item_const.attrs);
+        let expanded =
+            transform_value_item(&item_const.ident, &item_const.ty, quote! { #item_const });
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
+    }
+    if let Ok(item_static) = syn::parse2::<ItemStatic>(item.clone()) {
+        if let Err(error) = check_options(options, "statics", &[]) {
+            return error.to_compile_error();
+        }
+        let cfg_attrs = cfg_attrs(&item_static.attrs);
+        let guard = generate_symbol_guard(&item_static.ident, &item_static.attrs);
+        let expanded = if matches!(item_static.mutability, syn::StaticMutability::Mut(_)) {
+            let static_name = &item_static.ident;
+            quote! {
+                compile_error!(concat!(
+                    "#[julia] cannot export `static mut ", stringify!(#static_name),
+                    "`; reading it would be a data race. Use an atomic or a getter function."
+                ));
+            }
+        } else {
+            transform_value_item(&item_static.ident, &item_static.ty, quote! { #item_static })
         };
+        return apply_cfg_attrs(quote! { #guard #expanded }, &cfg_attrs);
     }
 
-    // Check if the return type is Result<T, E> or Option<T>
-    if let ReturnType::Type(_, ref ret_type) = func.sig.output {
-        if let Some(result_info) = extract_result_type(ret_type) {
-            return transform_result_function(func, result_info);
-        }
-        if let Some(option_info) = extract_option_type(ret_type) {
-            return transform_option_function(func, option_info);
+    // Try to parse as an impl block
+    if let Ok(item_impl) = syn::parse2::<ItemImpl>(item.clone()) {
+        if let Err(error) = check_options(options, "impl blocks", IMPL_OPTIONS) {
+            return error.to_compile_error();
         }
+        return transform_impl(item_impl, options);
     }
 
-    // Standard function transformation
+    // Try to parse as an inline module
+    if let Ok(item_mod) = syn::parse2::<ItemMod>(item.clone()) {
+        return transform_module(item_mod, options);
+    }
+
+    // If nothing matches, return an error
+    quote! {
+        compile_error!("#[julia] can only be applied to functions, structs, enums, type aliases, constants, statics, impl blocks, or modules");
+        #item
+    }
+}
+
+/// Transform an inline module with #[julia] attribute
+///
+/// Every `pub` function, struct and enum, every inherent impl block and every inline
+/// submodule is expanded as if it carried the module's `#[julia(...)]` itself. Items
+/// with their own `#[julia]` / `#[julia_pyo3]` are left to that attribute, and all other
+/// items (private helpers, `use`s, trait impls, ...) are kept unchanged.
+fn transform_module(mut item_mod: ItemMod, options: &JuliaOptions) -> TokenStream2 {
+    let Some((_, items)) = item_mod.content.take() else {
+        return syn::Error::new_spanned(
+            &item_mod,
+            "#[julia] on a module requires an inline body: `mod name { ... }`",
+        )
+        .to_compile_error();
+    };
+
+    // The module's options are shared by items of every kind; each uses those that apply
+    let item_options = JuliaOptions {
+        given: Vec::new(),
+        ..options.clone()
+    };
+    let is_pub = |vis: &Visibility| matches!(vis, Visibility::Public(_));
+    let mut expanded_items = TokenStream2::new();
+    for item in items {
+        let annotated = |attrs: &[Attribute]| {
+            attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("julia") || attr.path().is_ident("julia_pyo3"))
+        };
+        let exported = match &item {
+            syn::Item::Fn(func) => is_pub(&func.vis) && !annotated(&func.attrs),
+            syn::Item::Struct(item_struct) => {
+                is_pub(&item_struct.vis) && !annotated(&item_struct.attrs)
+            }
+            syn::Item::Enum(item_enum) => is_pub(&item_enum.vis) && !annotated(&item_enum.attrs),
+            syn::Item::Impl(item_impl) => {
+                item_impl.trait_.is_none() && !annotated(&item_impl.attrs)
+            }
+            syn::Item::Mod(nested) => nested.content.is_some() && !annotated(&nested.attrs),
+            _ => false,
+        };
+        if exported {
+            expanded_items.extend(expand_julia_item(quote! { #item }, &item_options));
+        } else {
+            expanded_items.extend(quote! { #item });
+        }
+    }
+
+    let attrs = &item_mod.attrs;
+    let vis = &item_mod.vis;
+    let unsafety = &item_mod.unsafety;
+    let mod_token = &item_mod.mod_token;
+    let ident = &item_mod.ident;
+    quote! {
+        #(#attrs)*
+        #vis #unsafety #mod_token #ident {
+            #expanded_items
+        }
+    }
+}
+
+/// Attach `#[cfg(feature = "...")]` to every top-level item of a `#[julia]` expansion
+///
+/// This covers the user's item as well as the generated wrappers, helper types and
+/// guard macros, so the whole export disappears when the feature is off.
+fn apply_cfg_feature(expanded: TokenStream2, feature: &LitStr) -> TokenStream2 {
+    apply_cfg_attrs(expanded, &[syn::parse_quote!(#[cfg(feature = #feature)])])
+}
+
+/// Switch the `extern "C"` functions of a `#[julia]` function expansion to `abi`
+fn apply_abi(expanded: TokenStream2, abi: &LitStr) -> TokenStream2 {
+    let mut file = match syn::parse2::<syn::File>(expanded.clone()) {
+        Ok(file) => file,
+        // Leave unparsable output (e.g. a compile_error!) for the compiler to report
+        Err(_) => return expanded,
+    };
+    for item in &mut file.items {
+        if let syn::Item::Fn(func) = item {
+            if let Some(name) = func.sig.abi.as_mut().and_then(|abi| abi.name.as_mut()) {
+                if name.value() == "C" {
+                    *name = abi.clone();
+                }
+            }
+        }
+    }
+    quote! { #file }
+}
+
+/// Export the wrapper named `func_name` as `link_name` instead of its Rust name
+///
+/// Only the wrapper's `#[no_mangle]` becomes `#[export_name = "..."]`; helper exports
+/// derived from the function name (`<fn>_last_error`, `<fn>_free`, ...) keep their names.
+fn apply_link_name(expanded: TokenStream2, func_name: &Ident, link_name: &Ident) -> TokenStream2 {
+    let mut file = match syn::parse2::<syn::File>(expanded.clone()) {
+        Ok(file) => file,
+        // Leave unparsable output (e.g. a compile_error!) for the compiler to report
+        Err(_) => return expanded,
+    };
+    let symbol = link_name.to_string();
+    for item in &mut file.items {
+        if let syn::Item::Fn(func) = item {
+            if func.sig.ident != *func_name {
+                continue;
+            }
+            for attr in &mut func.attrs {
+                if attr.path().is_ident("no_mangle") {
+                    *attr = syn::parse_quote!(#[export_name = #symbol]);
+                }
+            }
+        }
+    }
+    quote! { #file }
+}
+
+/// Mark the exported wrapper named `func_name` `#[deprecated]` with `note`
+///
+/// Only the wrapper is marked; the `<fn>_inner` helper it calls must stay warning-free.
+fn apply_deprecated(expanded: TokenStream2, func_name: &Ident, note: &LitStr) -> TokenStream2 {
+    let mut file = match syn::parse2::<syn::File>(expanded.clone()) {
+        Ok(file) => file,
+        // Leave unparsable output (e.g. a compile_error!) for the compiler to report
+        Err(_) => return expanded,
+    };
+    for item in &mut file.items {
+        if let syn::Item::Fn(func) = item {
+            let exported = func.attrs.iter().any(|attr| {
+                attr.path().is_ident("no_mangle") || attr.path().is_ident("export_name")
+            });
+            if func.sig.ident == *func_name && exported {
+                func.attrs
+                    .push(syn::parse_quote!(#[deprecated(note = #note)]));
+            }
+        }
+    }
+    quote! { #file }
+}
+
+/// Collect the `#[cfg(...)]` attributes of the item under `#[julia]`
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .cloned()
+        .collect()
+}
+
+/// Attach `cfg_attrs` to every top-level item of a `#[julia]` expansion
+///
+/// The inner function, the extern wrapper and any generated types must be gated
+/// together, otherwise a wrapper could survive without the code it calls.
+/// Items that already carry an attribute are not given a second copy.
+fn apply_cfg_attrs(expanded: TokenStream2, cfg_attrs: &[Attribute]) -> TokenStream2 {
+    if cfg_attrs.is_empty() {
+        return expanded;
+    }
+    let mut file = match syn::parse2::<syn::File>(expanded.clone()) {
+        Ok(file) => file,
+        // Leave unparsable output (e.g. a compile_error!) for the compiler to report
+        Err(_) => return expanded,
+    };
+    for item in &mut file.items {
+        let attrs = match item {
+            syn::Item::Const(item) => &mut item.attrs,
+            syn::Item::Enum(item) => &mut item.attrs,
+            syn::Item::Fn(item) => &mut item.attrs,
+            syn::Item::Impl(item) => &mut item.attrs,
+            syn::Item::Macro(item) => &mut item.attrs,
+            syn::Item::Mod(item) => &mut item.attrs,
+            syn::Item::Static(item) => &mut item.attrs,
+            syn::Item::Struct(item) => &mut item.attrs,
+            syn::Item::Trait(item) => &mut item.attrs,
+            syn::Item::Type(item) => &mut item.attrs,
+            syn::Item::Union(item) => &mut item.attrs,
+            syn::Item::Use(item) => &mut item.attrs,
+            _ => continue,
+        };
+        let missing: Vec<_> = cfg_attrs
+            .iter()
+            .filter(|attr| !attrs.contains(attr))
+            .cloned()
+            .collect();
+        attrs.splice(0..0, missing);
+    }
+    quote! { #file }
+}
+
+/// Generate a crate-wide marker for the `#[no_mangle]` symbols exported under `name`
+///
+/// `#[macro_export]` macros all live in the crate root's macro namespace, so two
+/// `#[julia]` items with the same name in different modules fail at compile time with
+/// "the name `__julia_no_mangle_<name>` is defined multiple times" instead of a
+/// duplicate symbol error at link time. `#[cfg]` attributes are copied so that
+/// mutually exclusive definitions don't collide.
+fn generate_symbol_guard(name: &Ident, attrs: &[Attribute]) -> TokenStream2 {
+    let guard_name = format_ident!("__julia_no_mangle_{}", name);
+    let cfg_attrs = attrs.iter().filter(|attr| attr.path().is_ident("cfg"));
+    quote! {
+        #(#cfg_attrs)*
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #guard_name {
+            () => {};
+        }
+    }
+}
+
+/// Transform a function with #[julia] attribute to FFI-compatible form
+fn transform_function(mut func: ItemFn, options: &JuliaOptions) -> TokenStream2 {
+    // Check for unsafe functions
+    if func.sig.unsafety.is_some() {
+        return quote! {
+            compile_error!("#[julia] cannot be applied to unsafe functions directly. The function will be made extern \"C\" which has its own safety semantics.");
+        };
+    }
+
+    // Destructuring patterns have no name to forward from the extern wrapper
+    for arg in &func.sig.inputs {
+        if let FnArg::Typed(pat_type) = arg {
+            if !matches!(pat_type.pat.as_ref(), Pat::Ident(_)) {
+                return syn::Error::new_spanned(
+                    &pat_type.pat,
+                    "#[julia] function parameters must be plain identifiers; \
+                     destructure inside the function body instead",
+                )
+                .to_compile_error();
+            }
+        }
+    }
+
+    if options.checked {
+        if let Err(err) = insert_slice_length_checks(&mut func) {
+            return err;
+        }
+    }
+
+    if let Some((elem, len)) = &options.scratch {
+        insert_scratch_helper(&mut func, elem, len);
+    }
+
+    if options.out_param && options.out_params {
+        let func_name = &func.sig.ident;
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] function `", stringify!(#func_name),
+                "` cannot use both out_param and out_params."
+            ));
+        };
+    }
+
+    if options.out_param {
+        return transform_out_param_function(func);
+    }
+
+    if options.out_params {
+        return transform_out_params_function(func);
+    }
+
+    if options.opaque {
+        return transform_opaque_function(func);
+    }
+
+    // Check if the return type is Result<T, E> or Option<T>
+    if let ReturnType::Type(_, ref ret_type) = func.sig.output {
+        if let Some(result_info) = extract_result_type(ret_type) {
+            let payloads = [&result_info.ok_type, &result_info.err_type];
+            if let Some(error) = check_zero_fillable(&func.sig.ident, &payloads) {
+                return error;
+            }
+            let guard = generate_zero_fill_guard(&func.sig.ident, &payloads);
+            // errno mode converts the error to an `i32` instead of storing it
+            let stored = if options.errno {
+                &payloads[..1]
+            } else {
+                &payloads[..]
+            };
+            let size_guard = generate_zero_size_guard(&func.sig.ident, stored);
+            let wrapper = if options.errno {
+                transform_errno_function(func, result_info)
+            } else {
+                transform_result_function(func, result_info, options.canonical)
+            };
+            return quote! { #guard #size_guard #wrapper };
+        }
+        if let Some(option_info) = extract_option_type(ret_type) {
+            let payloads = [&option_info.inner_type];
+            if let Some(error) = check_zero_fillable(&func.sig.ident, &payloads) {
+                return error;
+            }
+            let guard = generate_zero_fill_guard(&func.sig.ident, &payloads);
+            let size_guard = generate_zero_size_guard(&func.sig.ident, &payloads);
+            let wrapper = transform_option_function(func, option_info, options.canonical);
+            return quote! { #guard #size_guard #wrapper };
+        }
+        if !options.errno {
+            if let Some(boxed_type) = extract_box_type(ret_type) {
+                return transform_box_return_function(func, &boxed_type);
+            }
+            if is_static_cow_str_type(ret_type) {
+                return transform_cow_str_function(func);
+            }
+            if let Some(primitive) = nonzero_primitive(ret_type) {
+                return transform_nonzero_return_function(func, &primitive);
+            }
+            if let Some((key_type, value_type)) = extract_hashmap_types(ret_type) {
+                return transform_hashmap_return_function(func, &key_type, &value_type);
+            }
+            if let Type::Array(array) = &**ret_type {
+                if extract_array_elem_type(ret_type).is_some() {
+                    let array = array.clone();
+                    return transform_array_return_function(func, &array);
+                }
+            }
+        }
+    }
+
+    if !options.errno
+        && matches!(&func.sig.output, ReturnType::Type(_, ty) if is_static_str_type(ty))
+    {
+        return transform_static_str_function(func);
+    }
+
+    if options.errno {
+        let func_name = &func.sig.ident;
+        return quote! {
+            compile_error!(concat!(
+                "#[julia(errno)] function `", stringify!(#func_name),
+                "` must return Result<T, E>."
+            ));
+        };
+    }
+
+    // Standard function transformation
     transform_simple_function(func)
 }
 
 /// Transform a simple function (no Result/Option) to FFI-compatible form
 fn transform_simple_function(mut func: ItemFn) -> TokenStream2 {
+    // Lower non-FFI parameters (e.g. slices) and rebuild them at the top of the body
+    let lowered = lower_params(&func.sig.inputs);
+    if !lowered.prelude.is_empty() {
+        let prelude = &lowered.prelude;
+        let mut_rebinds = &lowered.mut_rebinds;
+        let prelude_block: syn::Block = syn::parse_quote!({ #prelude #mut_rebinds });
+        func.sig.inputs = lowered.params.into_iter().collect();
+        func.block.stmts.splice(0..0, prelude_block.stmts);
+    }
+
     // Add #[no_mangle]
     let no_mangle: Attribute = syn::parse_quote!(#[no_mangle]);
     func.attrs.insert(0, no_mangle);
@@ -322,12 +1587,300 @@ fn transform_simple_function(mut func: ItemFn) -> TokenStream2 {
     quote! { #func }
 }
 
+/// Extern-facing parameters of a `#[julia]` function
+struct LoweredParams {
+    /// Parameters of the generated `extern "C"` signature
+    params: Vec<FnArg>,
+    /// Statements rebuilding the original parameters from the lowered ones
+    prelude: TokenStream2,
+    /// `let mut x = x;` rebindings for `mut` parameters, whose `mut` is stripped from
+    /// the extern signature (only needed where the original body runs in place)
+    mut_rebinds: TokenStream2,
+}
+
+/// A `&[T]` or `&mut [T]` parameter bound to a plain identifier
+struct SliceParam<'a> {
+    name: &'a Ident,
+    elem: &'a Type,
+    mutable: bool,
+}
+
+/// Match a parameter of the form `name: &[T]` or `name: &mut [T]`
+fn slice_param(arg: &FnArg) -> Option<SliceParam<'_>> {
+    let FnArg::Typed(pat_type) = arg else {
+        return None;
+    };
+    let (Pat::Ident(pat_ident), Type::Reference(reference)) =
+        (pat_type.pat.as_ref(), pat_type.ty.as_ref())
+    else {
+        return None;
+    };
+    let Type::Slice(slice) = reference.elem.as_ref() else {
+        return None;
+    };
+    Some(SliceParam {
+        name: &pat_ident.ident,
+        elem: &slice.elem,
+        mutable: reference.mutability.is_some(),
+    })
+}
+
+/// Recognize a `name: &str` parameter
+fn str_param(arg: &FnArg) -> Option<Ident> {
+    let FnArg::Typed(pat_type) = arg else {
+        return None;
+    };
+    match (pat_type.pat.as_ref(), pat_type.ty.as_ref()) {
+        (Pat::Ident(pat_ident), Type::Reference(reference))
+            if reference.mutability.is_none()
+                && matches!(reference.elem.as_ref(), Type::Path(p) if p.path.is_ident("str")) =>
+        {
+            Some(pat_ident.ident.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Recognize a `name: &Struct` / `name: &mut Struct` parameter of a nested struct type
+fn struct_ref_param(arg: &FnArg) -> Option<(Ident, Type, bool)> {
+    let FnArg::Typed(pat_type) = arg else {
+        return None;
+    };
+    let (Pat::Ident(pat_ident), Type::Reference(reference)) =
+        (pat_type.pat.as_ref(), pat_type.ty.as_ref())
+    else {
+        return None;
+    };
+    is_nested_struct_type(&reference.elem).then(|| {
+        (
+            pat_ident.ident.clone(),
+            (*reference.elem).clone(),
+            reference.mutability.is_some(),
+        )
+    })
+}
+
+/// Lower parameters that cannot cross the C ABI into FFI-compatible ones
+///
+/// A slice parameter `xs: &[T]` becomes `xs_ptr: *const T, xs_len: usize` (`*mut T` for
+/// `&mut [T]`), and the prelude rebuilds `xs` (as an empty slice when `xs_len == 0`, so a
+/// null or dangling pointer is never dereferenced). A struct reference `p: &Point` becomes
+/// `p: *const Point` (`*mut Point` for `&mut Point`), reborrowed by the prelude; a null
+/// pointer panics instead of being dereferenced. A string slice `s: &str` becomes a
+/// NUL-terminated `s: *const c_char`, decoded lossily (invalid UTF-8 is replaced).
+/// A `n: NonZeroU32` becomes `n: u32`, and zero panics in the prelude.
+/// Other parameters pass through with any `mut` binding stripped, so wrappers that
+/// merely forward them don't warn.
+fn lower_params(inputs: &syn::punctuated::Punctuated<FnArg, syn::token::Comma>) -> LoweredParams {
+    let mut params = Vec::new();
+    let mut prelude = TokenStream2::new();
+    let mut mut_rebinds = TokenStream2::new();
+
+    for arg in inputs {
+        let mut arg = arg.clone();
+        if let FnArg::Typed(pat_type) = &mut arg {
+            if let Pat::Ident(pat_ident) = pat_type.pat.as_mut() {
+                if pat_ident.mutability.take().is_some() {
+                    let name = &pat_ident.ident;
+                    mut_rebinds.extend(quote! { let mut #name = #name; });
+                }
+            }
+        }
+
+        if let FnArg::Typed(pat_type) = &arg {
+            if let (Pat::Ident(pat_ident), true) =
+                (pat_type.pat.as_ref(), is_f16_type(&pat_type.ty))
+            {
+                let name = &pat_ident.ident;
+                let f16_ty = &pat_type.ty;
+                params.push(syn::parse_quote!(#name: u16));
+                prelude.extend(quote! { let #name = <#f16_ty>::from_bits(#name); });
+                continue;
+            }
+        }
+
+        if let FnArg::Typed(pat_type) = &arg {
+            if let (Pat::Ident(pat_ident), Some(primitive)) =
+                (pat_type.pat.as_ref(), nonzero_primitive(&pat_type.ty))
+            {
+                let name = &pat_ident.ident;
+                let nonzero_ty = &pat_type.ty;
+                params.push(syn::parse_quote!(#name: #primitive));
+                prelude.extend(quote! {
+                    let #name = <#nonzero_ty>::new(#name)
+                        .expect(concat!("zero passed as non-zero `", stringify!(#name), "`"));
+                });
+                continue;
+            }
+        }
+
+        if let Some(name) = str_param(&arg) {
+            params.push(syn::parse_quote!(#name: *const std::ffi::c_char));
+            prelude.extend(quote! {
+                assert!(!#name.is_null(), concat!("null pointer passed as `", stringify!(#name), "`"));
+                let #name = unsafe { std::ffi::CStr::from_ptr(#name) }.to_string_lossy();
+                let #name: &str = &#name;
+            });
+            continue;
+        }
+
+        if let Some((name, elem, mutable)) = struct_ref_param(&arg) {
+            let (ptr_ty, ref_ty, reborrow) = if mutable {
+                (quote!(*mut #elem), quote!(&mut #elem), quote!(as_mut))
+            } else {
+                (quote!(*const #elem), quote!(&#elem), quote!(as_ref))
+            };
+            params.push(syn::parse_quote!(#name: #ptr_ty));
+            prelude.extend(quote! {
+                let #name: #ref_ty = unsafe { #name.#reborrow() }
+                    .expect(concat!("null pointer passed as `", stringify!(#name), "`"));
+            });
+            continue;
+        }
+
+        let Some(SliceParam {
+            name,
+            elem,
+            mutable,
+        }) = slice_param(&arg)
+        else {
+            params.push(arg);
+            continue;
+        };
+
+        let ptr_name = format_ident!("{}_ptr", name);
+        let len_name = format_ident!("{}_len", name);
+        let (ptr_ty, slice_ty, empty, from_raw_parts) = if mutable {
+            (
+                quote!(*mut #elem),
+                quote!(&mut [#elem]),
+                quote!(&mut []),
+                quote!(std::slice::from_raw_parts_mut),
+            )
+        } else {
+            (
+                quote!(*const #elem),
+                quote!(&[#elem]),
+                quote!(&[]),
+                quote!(std::slice::from_raw_parts),
+            )
+        };
+
+        params.push(syn::parse_quote!(#ptr_name: #ptr_ty));
+        params.push(syn::parse_quote!(#len_name: usize));
+        prelude.extend(quote! {
+            let #name: #slice_ty = if #len_name == 0 {
+                #empty
+            } else {
+                unsafe { #from_raw_parts(#ptr_name, #len_name) }
+            };
+        });
+    }
+
+    LoweredParams {
+        params,
+        prelude,
+        mut_rebinds,
+    }
+}
+
+/// Prepend debug assertions that all slice parameters share one length (`checked`)
+fn insert_slice_length_checks(func: &mut ItemFn) -> Result<(), TokenStream2> {
+    let func_name = &func.sig.ident;
+    let names: Vec<Ident> = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(slice_param)
+        .map(|param| param.name.clone())
+        .collect();
+    if names.len() < 2 {
+        return Err(quote! {
+            compile_error!(concat!(
+                "#[julia(checked)] function `", stringify!(#func_name),
+                "` must take at least two slice parameters."
+            ));
+        });
+    }
+
+    let first = &names[0];
+    let message = format!("{}: slice arguments must have the same length", func_name);
+    let checks: Vec<syn::Stmt> = names[1..]
+        .iter()
+        .map(|name| syn::parse_quote!(debug_assert_eq!(#first.len(), #name.len(), #message);))
+        .collect();
+    func.block.stmts.splice(0..0, checks);
+    Ok(())
+}
+
+/// Prepend a `with_scratch(|buf| ...)` helper lending a thread-local buffer (`scratch`)
+///
+/// The buffer is taken out of its slot for the duration of the closure, so a reentrant
+/// call gets a fresh buffer instead of aliasing the one already lent out.
+fn insert_scratch_helper(func: &mut ItemFn, elem: &Type, len: &syn::LitInt) {
+    let helper: syn::Stmt = syn::parse_quote! {
+        #[allow(dead_code)]
+        fn with_scratch<R>(f: impl FnOnce(&mut [#elem]) -> R) -> R {
+            thread_local! {
+                static SCRATCH: std::cell::Cell<Vec<#elem>> = const { std::cell::Cell::new(Vec::new()) };
+            }
+            let mut buf = SCRATCH.with(|slot| slot.take());
+            if buf.is_empty() {
+                buf = vec![<#elem as Default>::default(); #len];
+            }
+            let result = f(&mut buf);
+            SCRATCH.with(|slot| slot.set(buf));
+            result
+        }
+    };
+    func.block.stmts.insert(0, helper);
+}
+
+/// Attributes of the user's function to keep on the generated inner function
+///
+/// `#[inline]`, `#[cold]`, docs, lint allowances, ... apply to the user's body, which
+/// lives in the inner function. `#[cfg]` is left out here: `apply_cfg_attrs` gates the
+/// whole expansion, the wrapper included.
+fn forwarded_attrs(func: &ItemFn) -> Vec<&Attribute> {
+    func.attrs
+        .iter()
+        .filter(|attr| !attr.path().is_ident("julia") && !attr.path().is_ident("cfg"))
+        .collect()
+}
+
+/// Collect the identifiers of a function's typed arguments, for forwarding calls
+fn collect_arg_names(func: &ItemFn) -> Vec<Ident> {
+    func.sig
+        .inputs
+        .iter()
+        .filter_map(|arg| {
+            if let FnArg::Typed(pat_type) = arg {
+                if let Pat::Ident(pat_ident) = pat_type.pat.as_ref() {
+                    return Some(pat_ident.ident.clone());
+                }
+            }
+            None
+        })
+        .collect()
+}
+
 /// Transform a function returning Result<T, E> to FFI-compatible form
-fn transform_result_function(func: ItemFn, result_info: ResultTypeInfo) -> TokenStream2 {
+fn transform_result_function(
+    func: ItemFn,
+    result_info: ResultTypeInfo,
+    canonical: bool,
+) -> TokenStream2 {
     let func_name = &func.sig.ident;
     let ok_type = &result_info.ok_type;
     let err_type = &result_info.err_type;
 
+    // Result<Vec<T>, String> is lowered to a CVec payload and a C string error
+    if let Some(elem_type) = extract_vec_type(ok_type) {
+        if is_string_type(err_type) {
+            return transform_vec_string_result_function(func, &elem_type);
+        }
+    }
+
     // Check FFI compatibility early to avoid cascading errors
     if is_non_ffi_type(ok_type) {
         return quote! {
@@ -338,6 +1891,12 @@ fn transform_result_function(func: ItemFn, result_info: ResultTypeInfo) -> Token
             ));
         };
     }
+    if let Some(boxed_err_type) = extract_box_type(err_type) {
+        return transform_boxed_err_result_function(func, ok_type, &boxed_err_type);
+    }
+    if is_string_type(err_type) {
+        return transform_string_err_result_function(func, ok_type);
+    }
     if is_non_ffi_type(err_type) {
         return quote! {
             compile_error!(concat!(
@@ -348,25 +1907,34 @@ fn transform_result_function(func: ItemFn, result_info: ResultTypeInfo) -> Token
         };
     }
 
-    // Generate C-compatible result type
-    let c_result_type = generate_c_result_type(func_name, ok_type, err_type);
-    let result_type_name = format_ident!("CResult_{}", func_name);
+    // Result<(), E> has no Ok payload; use a struct without an ok_value field
+    if matches!(ok_type, Type::Tuple(tuple) if tuple.elems.is_empty()) {
+        return transform_unit_result_function(func, err_type);
+    }
+
+    // Result<*mut T, E> reports failure with a null pointer rather than zeroed bytes
+    if let Type::Ptr(ok_ptr) = ok_type {
+        return transform_ptr_result_function(func, ok_ptr, err_type);
+    }
+
+    // Generate C-compatible result type, named after the function; canonical types are
+    // named after the payloads and defined once by julia_canonical!
+    let (type_suffix, c_result_type) = if canonical {
+        (
+            canonical_result_suffix(ok_type, err_type),
+            TokenStream2::new(),
+        )
+    } else {
+        let c_result_type = generate_c_result_type(func_name, ok_type, err_type);
+        (func_name.clone(), c_result_type)
+    };
+    let result_type_name = format_ident!("CResult_{}", type_suffix);
 
     // Collect function arguments
-    let args: Vec<_> = func.sig.inputs.iter().collect();
-    let arg_names: Vec<_> = func
-        .sig
-        .inputs
-        .iter()
-        .filter_map(|arg| {
-            if let FnArg::Typed(pat_type) = arg {
-                if let Pat::Ident(pat_ident) = pat_type.pat.as_ref() {
-                    return Some(pat_ident.ident.clone());
-                }
-            }
-            None
-        })
-        .collect();
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
 
     // Get the original function body
     let body = &func.block;
@@ -374,186 +1942,2192 @@ fn transform_result_function(func: ItemFn, result_info: ResultTypeInfo) -> Token
     // Create the inner function that returns Result
     let inner_fn_name = format_ident!("{}_inner", func_name);
     let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #c_result_type
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, #err_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(value) => {
+                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
+                    let ptr = result.as_mut_ptr();
+                    unsafe {
+                        std::ptr::addr_of_mut!((*ptr).is_ok).write(1);
+                        std::ptr::addr_of_mut!((*ptr).ok_value).write(value);
+                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).err_value), 0, 1);
+                        result.assume_init()
+                    }
+                },
+                Err(err) => {
+                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
+                    let ptr = result.as_mut_ptr();
+                    unsafe {
+                        std::ptr::addr_of_mut!((*ptr).is_ok).write(0);
+                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).ok_value), 0, 1);
+                        std::ptr::addr_of_mut!((*ptr).err_value).write(err);
+                        result.assume_init()
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Transform a function returning Result<*mut T, E> (or `*const T`) to FFI-compatible form
+///
+/// Returns `CResultPtr_<fn> { is_ok, ptr, err }`, where `ptr` is null on `Err` and
+/// `err` is zero-filled on `Ok`.
+fn transform_ptr_result_function(
+    func: ItemFn,
+    ok_type: &syn::TypePtr,
+    err_type: &Type,
+) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let result_type_name = format_ident!("CResultPtr_{}", func_name);
+    let null_ptr = if ok_type.mutability.is_some() {
+        quote! { std::ptr::null_mut() }
+    } else {
+        quote! { std::ptr::null() }
+    };
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct #result_type_name {
+            pub is_ok: u8,
+            pub ptr: #ok_type,
+            pub err: #err_type,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, #err_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(ptr) => {
+                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
+                    let out = result.as_mut_ptr();
+                    unsafe {
+                        std::ptr::addr_of_mut!((*out).is_ok).write(1);
+                        std::ptr::addr_of_mut!((*out).ptr).write(ptr);
+                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*out).err), 0, 1);
+                        result.assume_init()
+                    }
+                },
+                Err(err) => #result_type_name {
+                    is_ok: 0,
+                    ptr: #null_ptr,
+                    err,
+                },
+            }
+        }
+    }
+}
+
+/// Transform a function returning Result<T, Box<E>> to FFI-compatible form
+///
+/// The error is handed over as `err_value: *mut E` (null on `Ok`), and
+/// `CResult_<fn>_free` releases it, so `E` is typically a `#[julia]` struct whose
+/// fields Julia reads through its accessors.
+fn transform_boxed_err_result_function(
+    func: ItemFn,
+    ok_type: &Type,
+    err_type: &Type,
+) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let result_type_name = format_ident!("CResult_{}", func_name);
+    let free_fn_name = format_ident!("CResult_{}_free", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        pub struct #result_type_name {
+            pub is_ok: u8,
+            pub ok_value: #ok_type,
+            pub err_value: *mut #err_type,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, Box<#err_type>> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(value) => #result_type_name {
+                    is_ok: 1,
+                    ok_value: value,
+                    err_value: std::ptr::null_mut(),
+                },
+                Err(err) => {
+                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
+                    let ptr = result.as_mut_ptr();
+                    unsafe {
+                        std::ptr::addr_of_mut!((*ptr).is_ok).write(0);
+                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).ok_value), 0, 1);
+                        std::ptr::addr_of_mut!((*ptr).err_value).write(Box::into_raw(err));
+                        result.assume_init()
+                    }
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #free_fn_name(result: #result_type_name) {
+            if !result.err_value.is_null() {
+                unsafe { drop(Box::from_raw(result.err_value)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning Result<Vec<T>, String> to FFI-compatible form
+///
+/// The Ok vector is handed over as `CVec_<fn> { ptr, len, cap }` (the `rust_helpers`
+/// `CVec` layout) and the Err message as a NUL-terminated `*mut c_char`.
+/// `CResult_<fn>_free` releases whichever arm is populated.
+fn transform_vec_string_result_function(func: ItemFn, elem_type: &Type) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let result_type_name = format_ident!("CResult_{}", func_name);
+    let vec_type_name = format_ident!("CVec_{}", func_name);
+    let free_fn_name = format_ident!("CResult_{}_free", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        pub struct #vec_type_name {
+            pub ptr: *mut #elem_type,
+            pub len: usize,
+            pub cap: usize,
+        }
+
+        #[repr(C)]
+        pub struct #result_type_name {
+            pub is_ok: u8,
+            pub ok_value: #vec_type_name,
+            pub err_value: *mut std::ffi::c_char,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<Vec<#elem_type>, String> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(value) => {
+                    let mut value = std::mem::ManuallyDrop::new(value);
+                    #result_type_name {
+                        is_ok: 1,
+                        ok_value: #vec_type_name {
+                            ptr: value.as_mut_ptr(),
+                            len: value.len(),
+                            cap: value.capacity(),
+                        },
+                        err_value: std::ptr::null_mut(),
+                    }
+                },
+                Err(err) => #result_type_name {
+                    is_ok: 0,
+                    ok_value: #vec_type_name {
+                        ptr: std::ptr::null_mut(),
+                        len: 0,
+                        cap: 0,
+                    },
+                    // Interior NUL bytes cannot be represented in a C string
+                    err_value: std::ffi::CString::new(err.replace('\0', ""))
+                        .unwrap_or_default()
+                        .into_raw(),
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #free_fn_name(result: #result_type_name) {
+            if !result.ok_value.ptr.is_null() {
+                unsafe {
+                    drop(Vec::from_raw_parts(
+                        result.ok_value.ptr,
+                        result.ok_value.len,
+                        result.ok_value.cap,
+                    ));
+                }
+            }
+            if !result.err_value.is_null() {
+                unsafe { drop(std::ffi::CString::from_raw(result.err_value)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning `HashMap<K, V>` into parallel key and value vectors
+///
+/// The wrapper returns `CMap_<fn> { keys, values }`, two buffers in the `rust_helpers`
+/// `CVec` layout where `values[i]` belongs to `keys[i]`; the pair order is unspecified.
+/// `CMap_<fn>_free` releases both.
+fn transform_hashmap_return_function(
+    func: ItemFn,
+    key_type: &Type,
+    value_type: &Type,
+) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let map_type_name = format_ident!("CMap_{}", func_name);
+    let keys_type_name = format_ident!("CVec_{}_keys", func_name);
+    let values_type_name = format_ident!("CVec_{}_values", func_name);
+    let free_fn_name = format_ident!("CMap_{}_free", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let output = &func.sig.output;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        pub struct #keys_type_name {
+            pub ptr: *mut #key_type,
+            pub len: usize,
+            pub cap: usize,
+        }
+
+        #[repr(C)]
+        pub struct #values_type_name {
+            pub ptr: *mut #value_type,
+            pub len: usize,
+            pub cap: usize,
+        }
+
+        #[repr(C)]
+        pub struct #map_type_name {
+            pub keys: #keys_type_name,
+            pub values: #values_type_name,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) #output #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #map_type_name {
+            #prelude
+            let (keys, values): (Vec<#key_type>, Vec<#value_type>) =
+                #inner_fn_name(#(#arg_names),*).into_iter().unzip();
+            let mut keys = std::mem::ManuallyDrop::new(keys);
+            let mut values = std::mem::ManuallyDrop::new(values);
+            #map_type_name {
+                keys: #keys_type_name {
+                    ptr: keys.as_mut_ptr(),
+                    len: keys.len(),
+                    cap: keys.capacity(),
+                },
+                values: #values_type_name {
+                    ptr: values.as_mut_ptr(),
+                    len: values.len(),
+                    cap: values.capacity(),
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #free_fn_name(map: #map_type_name) {
+            if !map.keys.ptr.is_null() {
+                unsafe { drop(Vec::from_raw_parts(map.keys.ptr, map.keys.len, map.keys.cap)); }
+            }
+            if !map.values.ptr.is_null() {
+                unsafe { drop(Vec::from_raw_parts(map.values.ptr, map.values.len, map.values.cap)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning Result<T, String> to FFI-compatible form
+///
+/// The wrapper returns `T` directly (zeroed on error) and stores the error message in a
+/// per-function thread-local slot, taken by `<fn>_last_error() -> *mut c_char` and
+/// released with `<fn>_last_error_free`.
+fn transform_string_err_result_function(func: ItemFn, ok_type: &Type) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let last_error_fn_name = format_ident!("{}_last_error", func_name);
+    let last_error_free_fn_name = format_ident!("{}_last_error_free", func_name);
+    let slot_fn_name = format_ident!("__julia_error_slot_{}", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let ret = if matches!(ok_type, Type::Tuple(tuple) if tuple.elems.is_empty()) {
+        quote! {}
+    } else {
+        quote! { -> #ok_type }
+    };
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, String> #body
+
+        #[doc(hidden)]
+        fn #slot_fn_name(
+        ) -> &'static std::thread::LocalKey<std::cell::RefCell<Option<std::ffi::CString>>> {
+            thread_local! {
+                static LAST_ERROR: std::cell::RefCell<Option<std::ffi::CString>> =
+                    const { std::cell::RefCell::new(None) };
+            }
+            &LAST_ERROR
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) #ret {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(value) => {
+                    #slot_fn_name().with(|slot| slot.borrow_mut().take());
+                    value
+                },
+                Err(err) => {
+                    // Interior NUL bytes cannot be represented in a C string
+                    let message = std::ffi::CString::new(err.replace('\0', "")).unwrap_or_default();
+                    #slot_fn_name().with(|slot| *slot.borrow_mut() = Some(message));
+                    unsafe { std::mem::MaybeUninit::<#ok_type>::zeroed().assume_init() }
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #last_error_fn_name() -> *mut std::ffi::c_char {
+            #slot_fn_name()
+                .with(|slot| slot.borrow_mut().take())
+                .map_or(std::ptr::null_mut(), std::ffi::CString::into_raw)
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #last_error_free_fn_name(message: *mut std::ffi::c_char) {
+            if !message.is_null() {
+                unsafe { drop(std::ffi::CString::from_raw(message)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning Result<(), E> to FFI-compatible form
+///
+/// Emits `CResultUnit_<fn> { is_ok, err_value }`, omitting the meaningless `()` payload.
+fn transform_unit_result_function(func: ItemFn, err_type: &Type) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let result_type_name = format_ident!("CResultUnit_{}", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        pub struct #result_type_name {
+            pub is_ok: u8,
+            pub err_value: #err_type,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<(), #err_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(()) => {
+                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
+                    let ptr = result.as_mut_ptr();
+                    unsafe {
+                        std::ptr::addr_of_mut!((*ptr).is_ok).write(1);
+                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).err_value), 0, 1);
+                        result.assume_init()
+                    }
+                },
+                Err(err) => #result_type_name {
+                    is_ok: 0,
+                    err_value: err,
+                },
+            }
+        }
+    }
+}
+
+/// Transform a `#[julia(errno)]` function returning Result<T, E>
+///
+/// The wrapper returns `T` directly (zeroed on `Err`) and records the error in the
+/// thread-local state declared by `julia_errno!()`: the code via `i32::from(err)`
+/// and the message via `Display`. A successful call clears both.
+fn transform_errno_function(func: ItemFn, result_info: ResultTypeInfo) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let ok_type = &result_info.ok_type;
+    let err_type = &result_info.err_type;
+
+    // Check FFI compatibility early to avoid cascading errors
+    if is_non_ffi_type(ok_type) {
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] function `", stringify!(#func_name),
+                "` returns Result with non-FFI-compatible Ok type `", stringify!(#ok_type),
+                "`. Use a primitive or #[repr(C)] type instead."
+            ));
+        };
+    }
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, #err_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #ok_type {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Ok(value) => {
+                    crate::__julia_errno::clear();
+                    value
+                },
+                Err(err) => {
+                    let message = err.to_string();
+                    crate::__julia_errno::set(i32::from(err), message);
+                    unsafe { std::mem::MaybeUninit::<#ok_type>::zeroed().assume_init() }
+                },
+            }
+        }
+    }
+}
+
+/// Transform a function so its return value is written through an `out` pointer
+fn transform_out_param_function(func: ItemFn) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let ret_type = match &func.sig.output {
+        ReturnType::Type(_, ty)
+            if extract_result_type(ty).is_none() && extract_option_type(ty).is_none() =>
+        {
+            ty
+        }
+        _ => {
+            return quote! {
+                compile_error!(concat!(
+                    "#[julia(out_param)] function `", stringify!(#func_name),
+                    "` must return a plain value (not (), Result or Option)."
+                ));
+            };
+        }
+    };
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> #ret_type #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args,)* out: *mut #ret_type) {
+            #prelude
+            let value = #inner_fn_name(#(#arg_names),*);
+            if !out.is_null() {
+                unsafe { std::ptr::write(out, value) };
+            }
+        }
+    }
+}
+
+/// Transform a tuple-returning function so each element is written through an out pointer
+fn transform_out_params_function(func: ItemFn) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let elems: Vec<&Type> = match &func.sig.output {
+        ReturnType::Default => Vec::new(),
+        ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Tuple(tuple) => tuple.elems.iter().collect(),
+            _ => {
+                return quote! {
+                    compile_error!(concat!(
+                        "#[julia(out_params)] function `", stringify!(#func_name),
+                        "` must return a tuple; use out_param for a single value."
+                    ));
+                };
+            }
+        },
+    };
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_output = &func.sig.output;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let out_names: Vec<Ident> = (0..elems.len())
+        .map(|index| format_ident!("out{}", index))
+        .collect();
+    let values: Vec<Ident> = (0..elems.len())
+        .map(|index| format_ident!("value{}", index))
+        .collect();
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) #inner_fn_output #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args,)* #(#out_names: *mut #elems),*) {
+            #prelude
+            let (#(#values,)*) = #inner_fn_name(#(#arg_names),*);
+            #(
+                if !#out_names.is_null() {
+                    unsafe { std::ptr::write(#out_names, #values) };
+                }
+            )*
+        }
+    }
+}
+
+/// Transform a function returning Box<T> so ownership passes to Julia as `*mut T`
+///
+/// The pointer can be released with the `<T>_free` generated for `#[julia]` structs.
+fn transform_box_return_function(func: ItemFn, boxed_type: &Type) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let into_raw = generate_tracked_box(
+        &quote! { #boxed_type },
+        quote! { #inner_fn_name(#(#arg_names),*) },
+    );
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Box<#boxed_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> *mut #boxed_type {
+            #prelude
+            #into_raw
+        }
+    }
+}
+
+/// Transform a function returning `Box<T>` (typically `Box<dyn Trait>`) into an opaque handle
+///
+/// The box is boxed again so the fat trait-object pointer becomes a thin `*mut c_void`;
+/// `<fn>_free` reconstructs the outer box and drops both.
+fn transform_opaque_function(func: ItemFn) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let boxed = match &func.sig.output {
+        ReturnType::Type(_, ty)
+            if matches!(ty.as_ref(), Type::Path(type_path)
+                if type_path.path.segments.last().is_some_and(|segment| segment.ident == "Box")) =>
+        {
+            ty
+        }
+        _ => {
+            return quote! {
+                compile_error!(concat!(
+                    "#[julia(opaque)] function `", stringify!(#func_name),
+                    "` must return a Box, such as Box<dyn Trait>."
+                ));
+            };
+        }
+    };
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let free_fn_name = format_ident!("{}_free", func_name);
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> #boxed #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> *mut std::ffi::c_void {
+            #prelude
+            let handle: Box<#boxed> = Box::new(#inner_fn_name(#(#arg_names),*));
+            Box::into_raw(handle) as *mut std::ffi::c_void
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #free_fn_name(ptr: *mut std::ffi::c_void) {
+            if !ptr.is_null() {
+                unsafe { drop(Box::from_raw(ptr as *mut #boxed)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning a `&'static str` literal into a C string constant
+///
+/// The literal is baked into a NUL-terminated `static` byte string, so the wrapper returns
+/// `*const c_char` without allocating and Julia must not free it (unlike an owned `String`).
+/// Only a body consisting of a single string literal can be evaluated at expansion time.
+fn transform_static_str_function(func: ItemFn) -> TokenStream2 {
+    let literal = match func.block.stmts.as_slice() {
+        [syn::Stmt::Expr(
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(literal),
+                ..
+            }),
+            None,
+        )] => literal,
+        _ => {
+            return syn::Error::new_spanned(
+                &func.block,
+                "#[julia] functions returning &'static str must have a single string literal as their body",
+            )
+            .to_compile_error();
+        }
+    };
+    let text = literal.value();
+    if text.contains('\0') {
+        return syn::Error::new_spanned(literal, "a C string cannot contain interior NUL bytes")
+            .to_compile_error();
+    }
+    let bytes = syn::LitByteStr::new(format!("{text}\0").as_bytes(), literal.span());
+
+    let func_name = &func.sig.ident;
+    let args = &func.sig.inputs;
+    let attrs = forwarded_attrs(&func);
+
+    quote! {
+        #(#attrs)*
+        #[no_mangle]
+        pub extern "C" fn #func_name(#args) -> *const std::ffi::c_char {
+            static TEXT: &[u8] = #bytes;
+            TEXT.as_ptr() as *const std::ffi::c_char
+        }
+    }
+}
+
+/// Transform a function returning `Cow<'static, str>` into a C string with an ownership flag
+///
+/// The wrapper returns `CCowStr_<fn> { ptr, owned }`. A borrowed string is interned like a
+/// `&'static str` method return (`owned = 0`, never freed); an owned one is handed over as a
+/// `CString` (`owned = 1`). `CCowStr_<fn>_free` releases owned strings and ignores borrowed ones.
+fn transform_cow_str_function(func: ItemFn) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let cow_type_name = format_ident!("CCowStr_{}", func_name);
+    let free_fn_name = format_ident!("CCowStr_{}_free", func_name);
+
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let output = &func.sig.output;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let interned = generate_static_str_interning(quote! { text });
+
+    quote! {
+        #[repr(C)]
+        pub struct #cow_type_name {
+            pub ptr: *const std::ffi::c_char,
+            /// 1 if `ptr` was allocated at run time and must be freed with `CCowStr_<fn>_free`
+            pub owned: u8,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) #output #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #cow_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                std::borrow::Cow::Borrowed(text) => #cow_type_name {
+                    ptr: #interned,
+                    owned: 0,
+                },
+                // Interior NUL bytes cannot be represented in a C string
+                std::borrow::Cow::Owned(text) => #cow_type_name {
+                    ptr: std::ffi::CString::new(text.replace('\0', ""))
+                        .unwrap_or_default()
+                        .into_raw(),
+                    owned: 1,
+                },
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #free_fn_name(value: #cow_type_name) {
+            if value.owned != 0 && !value.ptr.is_null() {
+                unsafe { drop(std::ffi::CString::from_raw(value.ptr as *mut std::ffi::c_char)); }
+            }
+        }
+    }
+}
+
+/// Transform a function returning `NonZeroU32`-style types to return the plain integer
+///
+/// The value is never zero, so Julia sees an ordinary `u32` (etc.) return.
+fn transform_nonzero_return_function(func: ItemFn, primitive: &Ident) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let output = &func.sig.output;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) #output #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #primitive {
+            #prelude
+            #inner_fn_name(#(#arg_names),*).get()
+        }
+    }
+}
+
+/// Transform a function returning `[T; N]` so the array is returned inside a struct
+///
+/// C cannot return arrays by value, so the wrapper returns
+/// `#[repr(C)] CArr_<fn> { data: [T; N] }`, which Julia receives as an `NTuple{N, T}` field.
+fn transform_array_return_function(func: ItemFn, array: &syn::TypeArray) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let array_type_name = format_ident!("CArr_{}", func_name);
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        pub struct #array_type_name {
+            pub data: #array,
+        }
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> #array #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #array_type_name {
+            #prelude
+            #array_type_name {
+                data: #inner_fn_name(#(#arg_names),*),
+            }
+        }
+    }
+}
+
+/// Transform a function returning Option<T> to FFI-compatible form
+fn transform_option_function(
+    func: ItemFn,
+    option_info: OptionTypeInfo,
+    canonical: bool,
+) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let inner_type = &option_info.inner_type;
+
+    // Check FFI compatibility early to avoid cascading errors
+    if is_non_ffi_type(inner_type) {
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] function `", stringify!(#func_name),
+                "` returns Option with non-FFI-compatible type `", stringify!(#inner_type),
+                "`. Use a primitive or #[repr(C)] type instead."
+            ));
+        };
+    }
+
+    // Option<*mut T> is a bare pointer on the C side, with null meaning None
+    if let Type::Ptr(ptr_type) = inner_type {
+        return transform_option_ptr_function(func, ptr_type);
+    }
+
+    // Generate C-compatible option type, named after the function; canonical types are
+    // named after the payload and defined once by julia_canonical!
+    let (type_suffix, c_option_type) = if canonical {
+        (canonical_option_suffix(inner_type), TokenStream2::new())
+    } else {
+        (
+            func_name.clone(),
+            generate_c_option_type(func_name, inner_type),
+        )
+    };
+    let option_type_name = format_ident!("COption_{}", type_suffix);
+    let none_value = generate_c_option_none(&option_type_name);
+
+    // Collect function arguments
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+
+    // Get the original function body
+    let body = &func.block;
+
+    // Create the inner function that returns Option
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+
+    quote! {
+        #c_option_type
+
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Option<#inner_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #option_type_name {
+            #prelude
+            match #inner_fn_name(#(#arg_names),*) {
+                Some(value) => #option_type_name {
+                    is_some: 1,
+                    value,
+                },
+                None => #none_value,
+            }
+        }
+    }
+}
+
+/// Transform a function returning `Option<*mut T>`/`Option<*const T>` into a bare pointer
+///
+/// `None` becomes null, so `Some(null)` is indistinguishable from `None` on the C side.
+fn transform_option_ptr_function(func: ItemFn, ptr_type: &syn::TypePtr) -> TokenStream2 {
+    let func_name = &func.sig.ident;
+    let lowered = lower_params(&func.sig.inputs);
+    let args = &lowered.params;
+    let prelude = &lowered.prelude;
+    let arg_names = collect_arg_names(&func);
+    let body = &func.block;
+    let inner_fn_name = format_ident!("{}_inner", func_name);
+    let inner_fn_args = &func.sig.inputs;
+    let inner_fn_attrs = forwarded_attrs(&func);
+    let null = if ptr_type.mutability.is_some() {
+        quote! { std::ptr::null_mut() }
+    } else {
+        quote! { std::ptr::null() }
+    };
+
+    quote! {
+        #(#inner_fn_attrs)*
+        fn #inner_fn_name(#inner_fn_args) -> Option<#ptr_type> #body
+
+        #[no_mangle]
+        pub extern "C" fn #func_name(#(#args),*) -> #ptr_type {
+            #prelude
+            #inner_fn_name(#(#arg_names),*).unwrap_or(#null)
+        }
+    }
+}
+
+/// Options given in a per-field `#[julia(...)]` attribute
+#[derive(Default)]
+struct FieldOptions {
+    /// Accessor suffix used instead of the field name (`rename = "pos_x"`)
+    rename: Option<Ident>,
+    /// `fn(T) -> bool` gating the setter, which then reports rejection (`validate = "check"`)
+    validate: Option<syn::Path>,
+    /// The field holds another `#[julia]` struct, accessed by pointer (`nested`)
+    nested: bool,
+}
+
+/// Take the per-field `#[julia(...)]` attributes off a struct's fields
+///
+/// Returns the options of each field, in declaration order.
+fn take_field_options(fields: &mut syn::Fields) -> syn::Result<Vec<FieldOptions>> {
+    let mut all_options = Vec::new();
+    for field in fields.iter_mut() {
+        let mut options = FieldOptions::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("julia"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    options.rename = Some(lit.parse::<Ident>()?);
+                    Ok(())
+                } else if meta.path.is_ident("validate") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    options.validate = Some(lit.parse::<syn::Path>()?);
+                    Ok(())
+                } else if meta.path.is_ident("nested") {
+                    if !matches!(field.ty, Type::Path(_)) {
+                        return Err(syn::Error::new_spanned(
+                            &field.ty,
+                            "#[julia(nested)] is only supported on fields of a struct type",
+                        ));
+                    }
+                    options.nested = true;
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unsupported #[julia] field option; expected `rename`, `validate` or `nested`",
+                    ))
+                }
+            })?;
+        }
+        field.attrs.retain(|attr| !attr.path().is_ident("julia"));
+        all_options.push(options);
+    }
+    Ok(all_options)
+}
+
+/// Transform a struct with #[julia] attribute
+fn transform_struct(mut item_struct: ItemStruct, options: &JuliaOptions) -> TokenStream2 {
+    let field_options = match take_field_options(&mut item_struct.fields) {
+        Ok(field_options) => field_options,
+        Err(error) => return error.to_compile_error(),
+    };
+    let struct_name = &item_struct.ident;
+    let _struct_name_str = struct_name.to_string();
+
+    // Heap fields (String, Vec, Box, ...) have repr(Rust) internals
+    let heap_fields: Vec<_> = item_struct
+        .fields
+        .iter()
+        .filter(|field| is_non_ffi_type(&field.ty))
+        .collect();
+
+    if options.verify_repr_c {
+        if let Some(field) = heap_fields.first() {
+            let field_ty = &field.ty;
+            let field_label = match &field.ident {
+                Some(ident) => ident.to_string(),
+                None => "0".to_string(),
+            };
+            return quote! {
+                compile_error!(concat!(
+                    "#[julia(verify_repr_c)] struct `", stringify!(#struct_name),
+                    "` has field `", #field_label, "` of type `", stringify!(#field_ty),
+                    "`, which is not repr(C)-safe. Use a primitive, raw pointer, or #[repr(C)] type."
+                ));
+            };
+        }
+    } else if !heap_fields.is_empty() {
+        // Document the pointer-only contract on the struct itself
+        let names: Vec<_> = heap_fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|ident| format!("`{}`", ident)))
+            .collect();
+        let doc = format!(
+            " Heap fields ({}) are not `repr(C)`-safe: pass `{}` across FFI by pointer only. \
+             `String`/`Vec` fields are readable through clone getters.",
+            names.join(", "),
+            struct_name
+        );
+        item_struct.attrs.push(syn::parse_quote!(#[doc = ""]));
+        item_struct.attrs.push(syn::parse_quote!(#[doc = #doc]));
+    }
+
+    // Add #[repr(C)] attribute
+    let repr_c: Attribute = syn::parse_quote!(#[repr(C)]);
+    item_struct.attrs.insert(0, repr_c);
+
+    // Make it pub if not already
+    item_struct.vis = Visibility::Public(syn::token::Pub::default());
+
+    // Generate FFI wrapper functions
+    let mut ffi_functions = TokenStream2::new();
+    let mut tracking_impl = TokenStream2::new();
+
+    // Generate _free function (unless the struct is never owned by Julia)
+    let free_fn_name = format_ident!("{}_free", struct_name);
+    if options.no_free && options.track_frees {
+        return quote! {
+            compile_error!(concat!(
+                "#[julia(no_free)] struct `", stringify!(#struct_name),
+                "` has no _free function to track; remove track_frees."
+            ));
+        };
+    } else if options.no_free {
+        // No _free: the struct is owned by Rust (e.g. a global) and must not be freed by Julia
+    } else if options.track_frees {
+        let invalid_frees_fn_name = format_ident!("{}_invalid_free_count", struct_name);
+        tracking_impl = quote! {
+            impl #struct_name {
+                /// Pointers handed to Julia and not yet freed
+                #[doc(hidden)]
+                pub fn __julia_live_pointers(
+                ) -> std::sync::MutexGuard<'static, std::collections::HashSet<usize>> {
+                    static LIVE: std::sync::OnceLock<std::sync::Mutex<std::collections::HashSet<usize>>> =
+                        std::sync::OnceLock::new();
+                    LIVE.get_or_init(Default::default)
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                }
+
+                /// Number of rejected frees of unregistered or already freed pointers
+                #[doc(hidden)]
+                pub fn __julia_invalid_frees() -> &'static std::sync::atomic::AtomicUsize {
+                    static INVALID: std::sync::atomic::AtomicUsize =
+                        std::sync::atomic::AtomicUsize::new(0);
+                    &INVALID
+                }
+
+                /// Register a pointer boxed for Julia (shadows the untracked fallback)
+                #[doc(hidden)]
+                pub fn __julia_track_alloc(ptr: *mut Self) -> *mut Self {
+                    Self::__julia_live_pointers().insert(ptr as usize);
+                    ptr
+                }
+            }
+        };
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #free_fn_name(ptr: *mut #struct_name) {
+                if ptr.is_null() {
+                    return;
+                }
+                if !#struct_name::__julia_live_pointers().remove(&(ptr as usize)) {
+                    // Unregistered or already freed: record it instead of corrupting the heap
+                    #struct_name::__julia_invalid_frees()
+                        .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    return;
+                }
+                unsafe { drop(Box::from_raw(ptr)); }
+            }
+
+            #[no_mangle]
+            pub extern "C" fn #invalid_frees_fn_name() -> usize {
+                #struct_name::__julia_invalid_frees().load(std::sync::atomic::Ordering::Relaxed)
+            }
+        });
+    } else {
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #free_fn_name(ptr: *mut #struct_name) {
+                if !ptr.is_null() {
+                    unsafe { drop(Box::from_raw(ptr)); }
+                }
+            }
+        });
+    }
+
+    // Generate _free_array, freeing each pointer through _free (null entries are skipped)
+    if !options.no_free {
+        let free_array_fn_name = format_ident!("{}_free_array", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #free_array_fn_name(ptrs: *mut *mut #struct_name, len: usize) {
+                if ptrs.is_null() {
+                    return;
+                }
+                for &ptr in unsafe { std::slice::from_raw_parts(ptrs, len) } {
+                    #free_fn_name(ptr);
+                }
+            }
+        });
+    }
+
+    // Instances shared across Julia threads must be Send + Sync
+    if options.thread_safe {
+        ffi_functions.extend(quote_spanned! {struct_name.span()=>
+            const _: fn() = || {
+                fn assert_send_sync<T: Send + Sync>() {}
+                assert_send_sync::<#struct_name>();
+            };
+        });
+    }
+
+    // Generate _swap function
+    let swap_fn_name = format_ident!("{}_swap", struct_name);
+    ffi_functions.extend(quote! {
+        #[no_mangle]
+        pub extern "C" fn #swap_fn_name(a: *mut #struct_name, b: *mut #struct_name) {
+            if a.is_null() || b.is_null() {
+                return;
+            }
+            unsafe { std::ptr::swap(a, b); }
+        }
+    });
+
+    // Generate _box taking ownership of a struct built by value on the Julia side
+    if !item_struct.fields.is_empty()
+        && item_struct
+            .fields
+            .iter()
+            .zip(&field_options)
+            .all(|(field, field_options)| field_options.nested || is_by_value_field_type(&field.ty))
+    {
+        let box_fn_name = format_ident!("{}_box", struct_name);
+        let boxed = generate_tracked_box(&quote!(#struct_name), quote!(Box::new(value)));
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #box_fn_name(value: #struct_name) -> *mut #struct_name {
+                #boxed
+            }
+        });
+    }
+
+    // Generate _next stepping the struct's Iterator impl
+    if let Some(item_ty) = &options.iterator {
+        let next_fn_name = format_ident!("{}_next", struct_name);
+        let c_option_type = generate_c_option_type(&next_fn_name, item_ty);
+        let option_type_name = format_ident!("COption_{}", next_fn_name);
+        let none_value = generate_c_option_none(&option_type_name);
+        ffi_functions.extend(quote! {
+            #c_option_type
+
+            #[no_mangle]
+            pub extern "C" fn #next_fn_name(ptr: *mut #struct_name) -> #option_type_name {
+                if ptr.is_null() {
+                    return #none_value;
+                }
+                let next: Option<#item_ty> = Iterator::next(unsafe { &mut *ptr });
+                match next {
+                    Some(value) => #option_type_name { is_some: 1, value },
+                    None => #none_value,
+                }
+            }
+        });
+    }
+
+    // Setters return () by default; checked setters report a rejected write as false
+    let (setter_ret, setter_reject, setter_done) = if options.checked_setters {
+        (
+            quote! { -> bool },
+            quote! { return false; },
+            quote! { true },
+        )
+    } else {
+        (quote! {}, quote! { return; }, quote! {})
+    };
+
+    // Generate _set_all writing every FFI-compatible field in one call
+    // (positional fields of tuple structs become parameters f0, f1, ...)
+    let mut set_all_members = Vec::new();
+    let mut set_all_params = Vec::new();
+    let mut set_all_types = Vec::new();
+    let mut set_all_values = Vec::new();
+    let mut set_all_checks = Vec::new();
+    for (index, field) in item_struct.fields.iter().enumerate() {
+        let split_only =
+            options.split_128 && is_128_bit_type(&field.ty) && !cfg!(feature = "i128_abi");
+        if !is_ffi_compatible_type(&field.ty) || split_only {
+            continue;
+        }
+        let param = match &field.ident {
+            Some(ident) => {
+                set_all_members.push(syn::Member::Named(ident.clone()));
+                ident.clone()
+            }
+            None => {
+                set_all_members.push(syn::Member::Unnamed(syn::Index::from(index)));
+                format_ident!("f{}", index)
+            }
+        };
+        let field_ty = &field.ty;
+        if let Some(validator) = &field_options[index].validate {
+            set_all_checks.push(quote! { #validator(#param) });
+        }
+        if is_f16_type(field_ty) {
+            set_all_types.push(quote! { u16 });
+            set_all_values.push(quote! { <#field_ty>::from_bits(#param) });
+        } else {
+            set_all_types.push(quote! { #field_ty });
+            set_all_values.push(quote! { #param });
+        }
+        set_all_params.push(param);
+    }
+    if !set_all_members.is_empty() && set_all_checks.is_empty() {
+        let set_all_fn_name = format_ident!("{}_set_all", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #set_all_fn_name(
+                ptr: *mut #struct_name,
+                #(#set_all_params: #set_all_types),*
+            ) #setter_ret {
+                if ptr.is_null() {
+                    #setter_reject
+                }
+                // Write through ptr directly: parameters are named after the fields
+                unsafe {
+                    #( (*ptr).#set_all_members = #set_all_values; )*
+                }
+                #setter_done
+            }
+        });
+    } else if !set_all_members.is_empty() {
+        // Validated fields: write nothing unless every validator accepts its value
+        let set_all_fn_name = format_ident!("{}_set_all", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #set_all_fn_name(
+                ptr: *mut #struct_name,
+                #(#set_all_params: #set_all_types),*
+            ) -> bool {
+                if ptr.is_null() #( || !#set_all_checks )* {
+                    return false;
+                }
+                unsafe {
+                    #( (*ptr).#set_all_members = #set_all_values; )*
+                }
+                true
+            }
+        });
+    }
+
+    // Generate _iter_new/_iter_next/_iter_free over contiguous arrays of the struct.
+    // The iterator state is a boxed (base, len, position) tuple behind an opaque pointer.
+    let iter_new_fn_name = format_ident!("{}_iter_new", struct_name);
+    let iter_next_fn_name = format_ident!("{}_iter_next", struct_name);
+    let iter_free_fn_name = format_ident!("{}_iter_free", struct_name);
+    ffi_functions.extend(quote! {
+        #[no_mangle]
+        pub extern "C" fn #iter_new_fn_name(base: *mut #struct_name, len: usize) -> *mut std::ffi::c_void {
+            let len = if base.is_null() { 0 } else { len };
+            Box::into_raw(Box::new((base, len, 0usize))) as *mut std::ffi::c_void
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #iter_next_fn_name(iter: *mut std::ffi::c_void) -> *mut #struct_name {
+            if iter.is_null() {
+                return std::ptr::null_mut();
+            }
+            let state = unsafe { &mut *(iter as *mut (*mut #struct_name, usize, usize)) };
+            let (base, len, position) = *state;
+            if position >= len {
+                return std::ptr::null_mut();
+            }
+            state.2 = position + 1;
+            unsafe { base.add(position) }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #iter_free_fn_name(iter: *mut std::ffi::c_void) {
+            if !iter.is_null() {
+                unsafe { drop(Box::from_raw(iter as *mut (*mut #struct_name, usize, usize))); }
+            }
+        }
+    });
+
+    // Generate _debug_string function when the struct is Debug
+    if options.debug || has_derive(&item_struct.attrs, "Debug") {
+        let debug_fn_name = format_ident!("{}_debug_string", struct_name);
+        let debug_free_fn_name = format_ident!("{}_debug_string_free", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #debug_fn_name(ptr: *const #struct_name) -> *mut std::ffi::c_char {
+                if ptr.is_null() {
+                    return std::ptr::null_mut();
+                }
+                let text = format!("{:?}", unsafe { &*ptr });
+                // Interior NUL bytes cannot be represented in a C string
+                std::ffi::CString::new(text.replace('\0', ""))
+                    .unwrap_or_default()
+                    .into_raw()
+            }
+
+            #[no_mangle]
+            pub extern "C" fn #debug_free_fn_name(text: *mut std::ffi::c_char) {
+                if !text.is_null() {
+                    unsafe { drop(std::ffi::CString::from_raw(text)); }
+                }
+            }
+        });
+    }
+
+    // Generate _hash function when the struct is Hash
+    if options.hash || has_derive(&item_struct.attrs, "Hash") {
+        let hash_fn_name = format_ident!("{}_hash", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #hash_fn_name(ptr: *const #struct_name) -> u64 {
+                if ptr.is_null() {
+                    return 0;
+                }
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                std::hash::Hash::hash(unsafe { &*ptr }, &mut hasher);
+                std::hash::Hasher::finish(&hasher)
+            }
+        });
+    }
+
+    // Generate _new_default constructor when the struct is Default
+    if options.default || has_derive(&item_struct.attrs, "Default") {
+        let new_default_fn_name = format_ident!("{}_new_default", struct_name);
+        let boxed = generate_tracked_box(
+            &quote!(#struct_name),
+            quote!(Box::new(<#struct_name as Default>::default())),
+        );
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #new_default_fn_name() -> *mut #struct_name {
+                #boxed
+            }
+        });
+    }
+
+    // Generate _diff_mask function: bit i is set when field i differs
+    if options.diff {
+        let field_count = item_struct.fields.len();
+        if field_count > 64 {
+            return quote! {
+                compile_error!(concat!(
+                    "#[julia(diff)] struct `", stringify!(#struct_name),
+                    "` has more than 64 fields, which do not fit in a u64 mask."
+                ));
+            };
+        }
+        let members: Vec<syn::Member> = item_struct
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(i)),
+            })
+            .collect();
+        let bits: Vec<u32> = (0..field_count as u32).collect();
+        let all_fields = if field_count == 64 {
+            u64::MAX
+        } else {
+            (1u64 << field_count) - 1
+        };
+        let diff_fn_name = format_ident!("{}_diff_mask", struct_name);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #diff_fn_name(a: *const #struct_name, b: *const #struct_name) -> u64 {
+                if a.is_null() || b.is_null() {
+                    return #all_fields;
+                }
+                let (a, b) = unsafe { (&*a, &*b) };
+                let mut mask = 0u64;
+                #(
+                    if a.#members != b.#members {
+                        mask |= 1u64 << #bits;
+                    }
+                )*
+                mask
+            }
+        });
+    }
+
+    // Generate field accessors (positional fields of tuple structs are named by index)
+    for (index, field) in item_struct.fields.iter().enumerate() {
+        let (field_name, field_label) = match &field.ident {
+            Some(ident) => (syn::Member::Named(ident.clone()), ident.to_string()),
+            None => (
+                syn::Member::Unnamed(syn::Index::from(index)),
+                index.to_string(),
+            ),
+        };
+        // #[julia(rename = "...")] changes the accessor suffix, not the field accessed
+        let field_label = match &field_options[index].rename {
+            Some(rename) => rename.to_string(),
+            None => field_label,
+        };
+        let field_ty = &field.ty;
+        let validator = &field_options[index].validate;
+        if let Some(validator) = validator {
+            let split = options.split_128 && is_128_bit_type(field_ty);
+            if !is_ffi_compatible_type(field_ty) || is_f16_type(field_ty) || split {
+                return syn::Error::new_spanned(
+                    validator,
+                    "#[julia(validate)] is only supported on primitive and pointer fields",
+                )
+                .to_compile_error();
+            }
+        }
+
+        if field_options[index].nested {
+            ffi_functions.extend(generate_nested_field_guard(
+                struct_name,
+                &field_label,
+                field_ty,
+            ));
+        }
+
+        // Report the field's size and position so Julia can check its layout assumptions
+        let width_fn_name = format_ident!("{}_field_width_{}", struct_name, field_label);
+        let offset_fn_name = format_ident!("{}_field_offset_{}", struct_name, field_label);
+        ffi_functions.extend(quote! {
+            #[no_mangle]
+            pub extern "C" fn #width_fn_name() -> u8 {
+                u8::try_from(std::mem::size_of::<#field_ty>()).unwrap_or(u8::MAX)
+            }
+
+            #[no_mangle]
+            pub extern "C" fn #offset_fn_name() -> usize {
+                std::mem::offset_of!(#struct_name, #field_name)
+            }
+        });
+
+        // 128-bit integers have no stable C ABI; expose them as two u64 halves
+        if options.split_128 && is_128_bit_type(field_ty) {
+            ffi_functions.extend(generate_split_128_accessors(
+                struct_name,
+                &field_name,
+                &field_label,
+                field_ty,
+                options,
+            ));
+            if !cfg!(feature = "i128_abi") {
+                continue;
+            }
+        }
+
+        // half::f16 fields are read and written as their u16 bit pattern
+        if is_f16_type(field_ty) {
+            let getter_name = format_ident!("{}_get_{}", struct_name, field_label);
+            let setter_name = format_ident!("{}_set_{}", struct_name, field_label);
+            let null_check = options.checked_setters.then(|| {
+                quote! {
+                    if ptr.is_null() {
+                        #setter_reject
+                    }
+                }
+            });
+            if options.safe_access {
+                // Same null-checked COption getter as other primitives, over the u16 bits
+                let bits_ty: Type = syn::parse_quote!(u16);
+                let c_option_type = generate_c_option_type(&getter_name, &bits_ty);
+                let option_type_name = format_ident!("COption_{}", getter_name);
+                let none_value = generate_c_option_none(&option_type_name);
+                ffi_functions.extend(quote! {
+                    #c_option_type
+
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #option_type_name {
+                        if ptr.is_null() {
+                            return #none_value;
+                        }
+                        #option_type_name {
+                            is_some: 1,
+                            value: unsafe { (*ptr).#field_name.to_bits() },
+                        }
+                    }
+                });
+            } else {
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> u16 {
+                        unsafe { (*ptr).#field_name.to_bits() }
+                    }
+                });
+            }
+            ffi_functions.extend(quote! {
+                #[no_mangle]
+                pub extern "C" fn #setter_name(ptr: *mut #struct_name, value: u16) #setter_ret {
+                    #null_check
+                    unsafe { (*ptr).#field_name = <#field_ty>::from_bits(value); }
+                    #setter_done
+                }
+            });
+            continue;
+        }
+
+        // Only generate accessors for FFI-compatible types
+        if is_ffi_compatible_type(field_ty) || needs_clone_for_getter(field_ty) {
+            // Getter
+            let getter_name = format_ident!("{}_get_{}", struct_name, field_label);
+
+            if needs_clone_for_getter(field_ty) {
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #field_ty {
+                        unsafe { (*ptr).#field_name.clone() }
+                    }
+                });
+            } else if options.safe_access && lacks_zero_value(field_ty) {
+                // A zero-filled COption payload would be an invalid fn pointer; the getter
+                // returns a nullable `Option<fn>` instead, `None` (null) for a null pointer
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> Option<#field_ty> {
+                        if ptr.is_null() {
+                            return None;
+                        }
+                        Some(unsafe { (*ptr).#field_name })
+                    }
+                });
+            } else if options.safe_access {
+                // Null-checked getter: absence is signaled instead of dereferencing
+                let c_option_type = generate_c_option_type(&getter_name, field_ty);
+                let option_type_name = format_ident!("COption_{}", getter_name);
+                let none_value = generate_c_option_none(&option_type_name);
+                ffi_functions.extend(quote! {
+                    #c_option_type
+
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #option_type_name {
+                        if ptr.is_null() {
+                            return #none_value;
+                        }
+                        #option_type_name {
+                            is_some: 1,
+                            value: unsafe { (*ptr).#field_name },
+                        }
+                    }
+                });
+            } else {
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #field_ty {
+                        unsafe { (*ptr).#field_name }
+                    }
+                });
+            }
+
+            // Setter (unchecked by default, like the getter)
+            let setter_name = format_ident!("{}_set_{}", struct_name, field_label);
+            if let Some(validator) = validator {
+                // Validated setters always report whether the value was written
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #setter_name(ptr: *mut #struct_name, value: #field_ty) -> bool {
+                        if ptr.is_null() || !#validator(value) {
+                            return false;
+                        }
+                        unsafe { (*ptr).#field_name = value; }
+                        true
+                    }
+                });
+            } else {
+                let null_check = options.checked_setters.then(|| {
+                    quote! {
+                        if ptr.is_null() {
+                            #setter_reject
+                        }
+                    }
+                });
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #setter_name(ptr: *mut #struct_name, value: #field_ty) #setter_ret {
+                        #null_check
+                        unsafe { (*ptr).#field_name = value; }
+                        #setter_done
+                    }
+                });
+            }
+
+            // Nullable pointer fields also get a checked getter that reports null as None
+            if matches!(field_ty, Type::Ptr(_)) {
+                let checked_name = format_ident!("{}_get_{}_checked", struct_name, field_label);
+                let c_option_type = generate_c_option_type(&checked_name, field_ty);
+                let option_type_name = format_ident!("COption_{}", checked_name);
+                let none_value = generate_c_option_none(&option_type_name);
+                ffi_functions.extend(quote! {
+                    #c_option_type
+
+                    #[no_mangle]
+                    pub extern "C" fn #checked_name(ptr: *const #struct_name) -> #option_type_name {
+                        if ptr.is_null() {
+                            return #none_value;
+                        }
+                        let value = unsafe { (*ptr).#field_name };
+                        if value.is_null() {
+                            return #none_value;
+                        }
+                        #option_type_name { is_some: 1, value }
+                    }
+                });
+            }
+        } else if let Some(elem_ty) = extract_array_elem_type(field_ty) {
+            // Fixed-size array: indexed accessors, bounds-checked against N
+            let getter_name = format_ident!("{}_get_{}_at", struct_name, field_label);
+            let setter_name = format_ident!("{}_set_{}_at", struct_name, field_label);
+            if matches!(elem_ty, Type::BareFn(_)) {
+                // Fn pointers have no default value; a nullable `Option<fn>` is None (null)
+                // for a null pointer or an out-of-bounds index
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name, index: usize) -> Option<#elem_ty> {
+                        if ptr.is_null() {
+                            return None;
+                        }
+                        unsafe { (*ptr).#field_name.get(index).copied() }
+                    }
+                });
+            } else {
+                ffi_functions.extend(quote! {
+                    #[no_mangle]
+                    pub extern "C" fn #getter_name(ptr: *const #struct_name, index: usize) -> #elem_ty {
+                        if ptr.is_null() {
+                            return Default::default();
+                        }
+                        unsafe { (*ptr).#field_name.get(index).copied().unwrap_or_default() }
+                    }
+                });
+            }
+            ffi_functions.extend(quote! {
+                #[no_mangle]
+                pub extern "C" fn #setter_name(ptr: *mut #struct_name, index: usize, value: #elem_ty) #setter_ret {
+                    if ptr.is_null() {
+                        #setter_reject
+                    }
+                    let Some(slot) = (unsafe { (*ptr).#field_name.get_mut(index) }) else {
+                        #setter_reject
+                    };
+                    *slot = value;
+                    #setter_done
+                }
+            });
+        } else if let Some(inner_type) = extract_option_type(field_ty)
+            .map(|info| info.inner_type)
+            .filter(|inner| is_ffi_compatible_type(inner) && !lacks_zero_value(inner))
+        {
+            // Option<primitive>: nullable getter (is_some = 0 for None) and setter
+            let getter_name = format_ident!("{}_get_{}", struct_name, field_label);
+            let setter_name = format_ident!("{}_set_{}", struct_name, field_label);
+            let c_option_type = generate_c_option_type(&getter_name, &inner_type);
+            let option_type_name = format_ident!("COption_{}", getter_name);
+            let none_value = generate_c_option_none(&option_type_name);
+            ffi_functions.extend(quote! {
+                #c_option_type
+
+                #[no_mangle]
+                pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #option_type_name {
+                    if ptr.is_null() {
+                        return #none_value;
+                    }
+                    match unsafe { (*ptr).#field_name } {
+                        Some(value) => #option_type_name { is_some: 1, value },
+                        None => #none_value,
+                    }
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #setter_name(ptr: *mut #struct_name, is_some: u8, value: #inner_type) #setter_ret {
+                    if ptr.is_null() {
+                        #setter_reject
+                    }
+                    unsafe { (*ptr).#field_name = (is_some != 0).then_some(value); }
+                    #setter_done
+                }
+            });
+        } else if field_options[index].nested {
+            // Nested struct: borrow it in place by pointer, copy it in by clone
+            let getter_name = format_ident!("{}_get_{}", struct_name, field_label);
+            let setter_name = format_ident!("{}_set_{}", struct_name, field_label);
+            ffi_functions.extend(quote! {
+                #[no_mangle]
+                pub extern "C" fn #getter_name(ptr: *const #struct_name) -> *const #field_ty {
+                    if ptr.is_null() {
+                        return std::ptr::null();
+                    }
+                    unsafe { std::ptr::addr_of!((*ptr).#field_name) }
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #setter_name(ptr: *mut #struct_name, value: *const #field_ty) #setter_ret {
+                    if ptr.is_null() || value.is_null() {
+                        #setter_reject
+                    }
+                    unsafe { (*ptr).#field_name = (*value).clone(); }
+                    #setter_done
+                }
+            });
+        }
+    }
+
+    let alias_macro = generate_struct_alias_macro(struct_name, &ffi_functions);
+
+    quote! {
+        #item_struct
+
+        impl #struct_name {
+            /// Marks `#[julia]` structs for `#[julia(nested)]` fields of other structs
+            #[doc(hidden)]
+            pub const __JULIA_STRUCT: bool = true;
+        }
+
+        #tracking_impl
+
+        #ffi_functions
+
+        #alias_macro
+    }
+}
+
+/// Check if a type is `half::f16` (or an imported `f16`) with the `half` feature enabled
+///
+/// Such values cross the C ABI as their `u16` bit pattern.
+fn is_f16_type(ty: &Type) -> bool {
+    cfg!(feature = "half")
+        && matches!(ty, Type::Path(type_path) if type_path.qself.is_none()
+        && type_path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "f16" && segment.arguments.is_none()
+        }))
+}
+
+/// Check if a type is `u128` or `i128`
+fn is_128_bit_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("u128") || type_path.path.is_ident("i128"))
+}
+
+/// Generate `_get_<field>_lo`/`_hi` and `_set_<field>_lo`/`_hi` for a 128-bit field
+///
+/// Each half is a `u64` holding the two's complement bits, so `i128` values are
+/// reassembled on the Julia side as `(hi << 64) | lo` reinterpreted as signed.
+/// Like the other accessors, the getters return a `COption` under `safe_access` and the
+/// setters return `false` for a null pointer under `checked_setters`.
+fn generate_split_128_accessors(
+    struct_name: &Ident,
+    field_name: &syn::Member,
+    field_label: &str,
+    field_ty: &Type,
+    options: &JuliaOptions,
+) -> TokenStream2 {
+    let get_lo = format_ident!("{}_get_{}_lo", struct_name, field_label);
+    let get_hi = format_ident!("{}_get_{}_hi", struct_name, field_label);
+    let set_lo = format_ident!("{}_set_{}_lo", struct_name, field_label);
+    let set_hi = format_ident!("{}_set_{}_hi", struct_name, field_label);
+    // Work on the raw bits; the cast is only needed for the signed type
+    let (to_bits, from_bits) = if matches!(field_ty, Type::Path(p) if p.path.is_ident("i128")) {
+        (quote! { as u128 }, quote! { as i128 })
+    } else {
+        (quote! {}, quote! {})
+    };
+    let (setter_ret, null_check, setter_done) = if options.checked_setters {
+        (
+            quote! { -> bool },
+            quote! {
+                if ptr.is_null() {
+                    return false;
+                }
+            },
+            quote! { true },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
+    };
+
+    let half_ty: Type = syn::parse_quote!(u64);
+    let mut getters = TokenStream2::new();
+    for (getter_name, half) in [
+        (&get_lo, quote! { bits as u64 }),
+        (&get_hi, quote! { (bits >> 64) as u64 }),
+    ] {
+        if options.safe_access {
+            let c_option_type = generate_c_option_type(getter_name, &half_ty);
+            let option_type_name = format_ident!("COption_{}", getter_name);
+            let none_value = generate_c_option_none(&option_type_name);
+            getters.extend(quote! {
+                #c_option_type
+
+                #[no_mangle]
+                pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #option_type_name {
+                    if ptr.is_null() {
+                        return #none_value;
+                    }
+                    let bits = unsafe { (*ptr).#field_name } #to_bits;
+                    #option_type_name {
+                        is_some: 1,
+                        value: #half,
+                    }
+                }
+            });
+        } else {
+            getters.extend(quote! {
+                #[no_mangle]
+                pub extern "C" fn #getter_name(ptr: *const #struct_name) -> u64 {
+                    let bits = unsafe { (*ptr).#field_name } #to_bits;
+                    #half
+                }
+            });
+        }
+    }
+
+    quote! {
+        #getters
+
+        #[no_mangle]
+        pub extern "C" fn #set_lo(ptr: *mut #struct_name, lo: u64) #setter_ret {
+            #null_check
+            let bits = unsafe { (*ptr).#field_name } #to_bits;
+            let bits = (bits & !u128::from(u64::MAX)) | u128::from(lo);
+            unsafe { (*ptr).#field_name = bits #from_bits; }
+            #setter_done
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #set_hi(ptr: *mut #struct_name, hi: u64) #setter_ret {
+            #null_check
+            let bits = unsafe { (*ptr).#field_name } #to_bits;
+            let bits = (bits & u128::from(u64::MAX)) | (u128::from(hi) << 64);
+            unsafe { (*ptr).#field_name = bits #from_bits; }
+            #setter_done
+        }
+    }
+}
+
+/// Generate the hidden `__julia_struct_<Struct>!` macro used by `#[julia]` type aliases
+///
+/// The macro replays the signatures of the struct's generated FFI functions into
+/// `__julia_alias!`, which emits forwarding wrappers under the alias name.
+fn generate_struct_alias_macro(struct_name: &Ident, ffi_functions: &TokenStream2) -> TokenStream2 {
+    let macro_name = format_ident!("__julia_struct_{}", struct_name);
+    let signatures: Vec<_> = match syn::parse2::<syn::File>(ffi_functions.clone()) {
+        Ok(file) => file
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) => Some(func.sig),
+                _ => None,
+            })
+            .collect(),
+        Err(err) => return err.to_compile_error(),
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_name {
+            ($alias:ident) => {
+                ::juliacall_macros::__julia_alias! {
+                    $alias = #struct_name;
+                    #(#signatures;)*
+                }
+            };
+        }
+    }
+}
+
+/// Transform a `const`/`static` item, exporting its value as `<name>_value()`
+fn transform_value_item(name: &Ident, ty: &Type, item: TokenStream2) -> TokenStream2 {
+    let returnable = is_ffi_compatible_type(ty)
+        && !matches!(ty, Type::Tuple(_))
+        && !matches!(ty, Type::Path(p) if p.path.is_ident("char"));
+    if !returnable {
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] constant `", stringify!(#name), "` has type `", stringify!(#ty),
+                "`, which is not FFI-compatible. Use a primitive or raw pointer type."
+            ));
+        };
+    }
 
+    let value_fn_name = format_ident!("{}_value", name);
     quote! {
-        #c_result_type
-
-        fn #inner_fn_name(#inner_fn_args) -> Result<#ok_type, #err_type> #body
+        #item
 
         #[no_mangle]
-        pub extern "C" fn #func_name(#(#args),*) -> #result_type_name {
-            match #inner_fn_name(#(#arg_names),*) {
-                Ok(value) => {
-                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
-                    let ptr = result.as_mut_ptr();
-                    unsafe {
-                        std::ptr::addr_of_mut!((*ptr).is_ok).write(1);
-                        std::ptr::addr_of_mut!((*ptr).ok_value).write(value);
-                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).err_value), 0, 1);
-                        result.assume_init()
-                    }
-                },
-                Err(err) => {
-                    let mut result = std::mem::MaybeUninit::<#result_type_name>::uninit();
-                    let ptr = result.as_mut_ptr();
-                    unsafe {
-                        std::ptr::addr_of_mut!((*ptr).is_ok).write(0);
-                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).ok_value), 0, 1);
-                        std::ptr::addr_of_mut!((*ptr).err_value).write(err);
-                        result.assume_init()
-                    }
-                },
-            }
+        pub extern "C" fn #value_fn_name() -> #ty {
+            #name
         }
     }
 }
 
-/// Transform a function returning Option<T> to FFI-compatible form
-fn transform_option_function(func: ItemFn, option_info: OptionTypeInfo) -> TokenStream2 {
-    let func_name = &func.sig.ident;
-    let inner_type = &option_info.inner_type;
+/// Transform a type alias of a `#[julia]` struct
+fn transform_type_alias(mut item_type: ItemType) -> TokenStream2 {
+    let alias_name = &item_type.ident;
 
-    // Check FFI compatibility early to avoid cascading errors
-    if is_non_ffi_type(inner_type) {
+    if !item_type.generics.params.is_empty() {
         return quote! {
-            compile_error!(concat!(
-                "#[julia] function `", stringify!(#func_name),
-                "` returns Option with non-FFI-compatible type `", stringify!(#inner_type),
-                "`. Use a primitive or #[repr(C)] type instead."
-            ));
+            compile_error!("#[julia] cannot be applied to generic type aliases");
         };
     }
 
-    // Generate C-compatible option type
-    let c_option_type = generate_c_option_type(func_name, inner_type);
-    let option_type_name = format_ident!("COption_{}", func_name);
+    let target_name = match item_type.ty.as_ref() {
+        Type::Path(type_path)
+            if type_path.qself.is_none()
+                && type_path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.arguments.is_empty()) =>
+        {
+            type_path.path.segments.last().map(|s| s.ident.clone())
+        }
+        _ => None,
+    };
 
-    // Collect function arguments
-    let args: Vec<_> = func.sig.inputs.iter().collect();
-    let arg_names: Vec<_> = func
-        .sig
-        .inputs
-        .iter()
-        .filter_map(|arg| {
-            if let FnArg::Typed(pat_type) = arg {
-                if let Pat::Ident(pat_ident) = pat_type.pat.as_ref() {
-                    return Some(pat_ident.ident.clone());
-                }
+    let target_name = match target_name {
+        Some(name) => name,
+        None => {
+            return quote! {
+                compile_error!(concat!(
+                    "#[julia] type alias `", stringify!(#alias_name),
+                    "` must name a #[julia] struct directly, e.g. `type Vec2 = Point;`"
+                ));
             }
-            None
-        })
-        .collect();
+        }
+    };
 
-    // Get the original function body
-    let body = &func.block;
+    // Make it pub if not already
+    item_type.vis = Visibility::Public(syn::token::Pub::default());
 
-    // Create the inner function that returns Option
-    let inner_fn_name = format_ident!("{}_inner", func_name);
-    let inner_fn_args = &func.sig.inputs;
+    // Resolves only if the target was declared with #[julia]
+    let macro_name = format_ident!("__julia_struct_{}", target_name);
 
     quote! {
-        #c_option_type
+        #item_type
 
-        fn #inner_fn_name(#inner_fn_args) -> Option<#inner_type> #body
+        #macro_name!(#alias_name);
+    }
+}
 
-        #[no_mangle]
-        pub extern "C" fn #func_name(#(#args),*) -> #option_type_name {
-            match #inner_fn_name(#(#arg_names),*) {
-                Some(value) => #option_type_name {
-                    is_some: 1,
-                    value,
-                },
-                None => {
-                    let mut opt = std::mem::MaybeUninit::<#option_type_name>::uninit();
-                    let ptr = opt.as_mut_ptr();
-                    unsafe {
-                        std::ptr::addr_of_mut!((*ptr).is_some).write(0);
-                        std::ptr::write_bytes(std::ptr::addr_of_mut!((*ptr).value), 0, 1);
-                        opt.assume_init()
-                    }
-                },
-            }
+/// Input of `__julia_alias!`: `Alias = Target; fn Target_x(...) -> T; ...`
+struct AliasInput {
+    alias: Ident,
+    target: Ident,
+    signatures: Vec<syn::Signature>,
+}
+
+impl syn::parse::Parse for AliasInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let alias: Ident = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let target: Ident = input.parse()?;
+        input.parse::<syn::Token![;]>()?;
+
+        let mut signatures = Vec::new();
+        while !input.is_empty() {
+            signatures.push(input.parse()?);
+            input.parse::<syn::Token![;]>()?;
         }
+
+        Ok(AliasInput {
+            alias,
+            target,
+            signatures,
+        })
     }
 }
 
-/// Transform a struct with #[julia] attribute
-fn transform_struct(mut item_struct: ItemStruct) -> TokenStream2 {
-    let struct_name = &item_struct.ident;
-    let _struct_name_str = struct_name.to_string();
+/// Emit `<Alias>_*` wrappers forwarding to a struct's `<Struct>_*` FFI functions.
+///
+/// Implementation detail of `#[julia]` type aliases; not meant to be invoked directly.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __julia_alias(input: TokenStream) -> TokenStream {
+    let input = match syn::parse::<AliasInput>(input) {
+        Ok(input) => input,
+        Err(err) => return err.to_compile_error().into(),
+    };
 
-    // Add #[repr(C)] attribute
-    let repr_c: Attribute = syn::parse_quote!(#[repr(C)]);
-    item_struct.attrs.insert(0, repr_c);
+    let target_prefix = format!("{}_", input.target);
+    let mut wrappers = TokenStream2::new();
 
-    // Make it pub if not already
-    item_struct.vis = Visibility::Public(syn::token::Pub::default());
+    for sig in &input.signatures {
+        let target_fn = &sig.ident;
+        let suffix = match target_fn.to_string().strip_prefix(&target_prefix) {
+            Some(suffix) => suffix.to_string(),
+            None => continue,
+        };
 
-    // Generate FFI wrapper functions
-    let mut ffi_functions = TokenStream2::new();
+        let mut alias_sig = sig.clone();
+        alias_sig.ident = format_ident!("{}_{}", input.alias, suffix);
 
-    // Generate _free function
-    let free_fn_name = format_ident!("{}_free", struct_name);
-    ffi_functions.extend(quote! {
-        #[no_mangle]
-        pub extern "C" fn #free_fn_name(ptr: *mut #struct_name) {
-            if !ptr.is_null() {
-                unsafe { drop(Box::from_raw(ptr)); }
-            }
-        }
-    });
+        let arg_names: Vec<_> = sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => Some(pat_ident.ident.clone()),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
 
-    // Generate field accessors for named fields
-    if let syn::Fields::Named(ref fields) = item_struct.fields {
-        for field in &fields.named {
-            if let Some(ref field_name) = field.ident {
-                let field_ty = &field.ty;
+        wrappers.extend(quote! {
+            #[no_mangle]
+            pub #alias_sig {
+                #target_fn(#(#arg_names),*)
+            }
+        });
+    }
 
-                // Only generate accessors for FFI-compatible types
-                if is_ffi_compatible_type(field_ty) || needs_clone_for_getter(field_ty) {
-                    // Getter
-                    let getter_name = format_ident!("{}_get_{}", struct_name, field_name);
+    wrappers.into()
+}
 
-                    if needs_clone_for_getter(field_ty) {
-                        ffi_functions.extend(quote! {
-                            #[no_mangle]
-                            pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #field_ty {
-                                unsafe { (*ptr).#field_name.clone() }
-                            }
-                        });
-                    } else {
-                        ffi_functions.extend(quote! {
-                            #[no_mangle]
-                            pub extern "C" fn #getter_name(ptr: *const #struct_name) -> #field_ty {
-                                unsafe { (*ptr).#field_name }
-                            }
-                        });
-                    }
+/// Transform a fieldless enum with #[julia] attribute
+///
+/// The enum gets `#[repr(<int>)]` so its discriminants have a fixed width, and the
+/// conversion functions compare against the declared discriminants rather than
+/// variant positions, so explicit `= value` assignments are honored.
+fn transform_enum(mut item_enum: ItemEnum, options: &JuliaOptions) -> TokenStream2 {
+    let enum_name = &item_enum.ident;
 
-                    // Setter
-                    let setter_name = format_ident!("{}_set_{}", struct_name, field_name);
-                    ffi_functions.extend(quote! {
-                        #[no_mangle]
-                        pub extern "C" fn #setter_name(ptr: *mut #struct_name, value: #field_ty) {
-                            unsafe { (*ptr).#field_name = value; }
-                        }
-                    });
-                }
-            }
-        }
+    if !item_enum.generics.params.is_empty() {
+        return quote! {
+            compile_error!("#[julia] cannot be applied to generic enums");
+        };
+    }
+    if let Some(variant) = item_enum
+        .variants
+        .iter()
+        .find(|v| !matches!(v.fields, syn::Fields::Unit))
+    {
+        let variant_name = &variant.ident;
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] enum `", stringify!(#enum_name), "` has variant `",
+                stringify!(#variant_name),
+                "` with fields. Only fieldless enums can be passed through the C ABI."
+            ));
+        };
+    }
+    if item_enum
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("repr"))
+    {
+        return quote! {
+            compile_error!(concat!(
+                "#[julia] enum `", stringify!(#enum_name),
+                "` already has a #[repr] attribute. Use #[julia(repr = \"...\")] instead."
+            ));
+        };
     }
 
+    let repr = options.repr.clone().unwrap_or_else(|| format_ident!("i32"));
+
+    // Add #[repr(<int>)] attribute
+    let repr_attr: Attribute = syn::parse_quote!(#[repr(#repr)]);
+    item_enum.attrs.insert(0, repr_attr);
+
+    // Make it pub if not already
+    item_enum.vis = Visibility::Public(syn::token::Pub::default());
+
+    let variant_names: Vec<_> = item_enum.variants.iter().map(|v| &v.ident).collect();
+    let to_fn_name = format_ident!("{}_to_{}", enum_name, repr);
+    let is_valid_fn_name = format_ident!("{}_is_valid_{}", enum_name, repr);
+
     quote! {
-        #item_struct
+        #item_enum
 
-        #ffi_functions
+        #[no_mangle]
+        pub extern "C" fn #to_fn_name(value: #enum_name) -> #repr {
+            value as #repr
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #is_valid_fn_name(value: #repr) -> bool {
+            false #( || value == #enum_name::#variant_names as #repr )*
+        }
+
+        impl #enum_name {
+            /// Whether an all-zero value is a declared variant (checked by `#[julia]` functions
+            /// returning this enum inside `Result`/`Option`)
+            #[doc(hidden)]
+            pub const __JULIA_ZERO_IS_VALID: bool =
+                false #( || #enum_name::#variant_names as #repr == 0 )*;
+        }
     }
 }
 
+/// Helpers `transform_struct` exports for every struct, as `<Struct>_<suffix>`
+///
+/// A wrapped method with one of these names would export the same symbol.
+const STRUCT_HELPER_SUFFIXES: &[&str] =
+    &["swap", "free_array", "iter_new", "iter_next", "iter_free"];
+
 /// Transform an impl block with #[julia] attribute on methods
-fn transform_impl(mut item_impl: ItemImpl) -> TokenStream2 {
+fn transform_impl(mut item_impl: ItemImpl, options: &JuliaOptions) -> TokenStream2 {
+    // Trait methods would be exported under the struct's name (e.g. `Point_fmt` for Display)
+    if let Some((_, trait_path, _)) = &item_impl.trait_ {
+        return syn::Error::new_spanned(
+            trait_path,
+            "#[julia] supports only inherent impl blocks; wrap trait methods in an \
+             inherent `impl` and mark those methods #[julia] instead",
+        )
+        .to_compile_error();
+    }
+
     let self_ty = &item_impl.self_ty;
 
+    // Wrappers are named and called through the bare struct ident, so `impl Wrapper<i32>`
+    // would export `Wrapper_new` calling `Wrapper::new` and lose the specialization.
+    if !item_impl.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &item_impl.generics,
+            "#[julia] does not support generic impl blocks; declare a concrete alias such as \
+             `type WrapperI32 = Wrapper<i32>;` and write `impl WrapperI32` instead",
+        )
+        .to_compile_error();
+    }
+    if let Type::Path(type_path) = self_ty.as_ref() {
+        if let Some(segment) = type_path.path.segments.last() {
+            if !segment.arguments.is_none() {
+                let ident = &segment.ident;
+                let message = format!(
+                    "#[julia] impl blocks cannot name generic arguments on `{ident}`; declare a \
+                     concrete alias such as `type {ident}I32 = {ident}<i32>;` and write \
+                     `impl {ident}I32` instead"
+                );
+                return syn::Error::new_spanned(&segment.arguments, message).to_compile_error();
+            }
+        }
+    }
+
     // Extract the struct name from the type
     let struct_name = match self_ty.as_ref() {
         Type::Path(type_path) => type_path.path.segments.last().map(|s| s.ident.clone()),
@@ -574,13 +4148,50 @@ fn transform_impl(mut item_impl: ItemImpl) -> TokenStream2 {
     // Process each method in the impl block
     for item in &mut item_impl.items {
         if let syn::ImplItem::Fn(method) = item {
-            // Check if method has #[julia] attribute
+            // Check if method has #[julia] attribute (or is pub under #[julia(all)])
             let has_julia_attr = method
                 .attrs
                 .iter()
                 .any(|attr| attr.path().is_ident("julia"));
+            let is_pub = matches!(method.vis, Visibility::Public(_));
+            let mut skip = false;
+            for attr in method
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("julia"))
+            {
+                let args = match &attr.meta {
+                    syn::Meta::List(list) => list.tokens.clone(),
+                    _ => TokenStream2::new(),
+                };
+                match parse_julia_options(args).and_then(|method_options| {
+                    check_options(&method_options, "methods", METHOD_OPTIONS)?;
+                    Ok(method_options)
+                }) {
+                    Ok(method_options) => skip |= method_options.skip,
+                    Err(error) => return error.to_compile_error(),
+                }
+            }
+
+            if skip {
+                method.attrs.retain(|attr| !attr.path().is_ident("julia"));
+            } else if has_julia_attr || (options.all && is_pub) {
+                let method_name = &method.sig.ident;
+                if STRUCT_HELPER_SUFFIXES
+                    .iter()
+                    .any(|suffix| method_name == suffix)
+                {
+                    return syn::Error::new_spanned(
+                        method_name,
+                        format!(
+                            "method `{method_name}` would be exported as `{struct_name}_{method_name}`, \
+                             which #[julia] already generates for every struct; rename the method \
+                             or mark it #[julia(skip)]"
+                        ),
+                    )
+                    .to_compile_error();
+                }
 
-            if has_julia_attr {
                 // Remove #[julia] attribute from the method
                 method.attrs.retain(|attr| !attr.path().is_ident("julia"));
 
@@ -656,79 +4267,249 @@ fn generate_method_wrapper(struct_name: &Ident, method: &syn::ImplItemFn) -> Tok
     // Determine return type handling
     let return_type = &method.sig.output;
 
+    // The call to the original method (static or through the receiver)
+    let call = if is_static {
+        quote! { #struct_name::#method_name(#(#call_args),*) }
+    } else {
+        quote! { self_ref.#method_name(#(#call_args),*) }
+    };
+
+    let boxed = generate_tracked_box(&quote! { #struct_name }, quote! { Box::new(obj) });
+
+    // Fallible constructor: a static method returning Result<Self, E>
+    let is_fallible_constructor = is_static
+        && matches!(
+            return_type,
+            ReturnType::Type(_, ty) if extract_result_type(ty)
+                .is_some_and(|result| is_self_type(&result.ok_type, struct_name))
+        );
+
+    if is_fallible_constructor {
+        // Returns *mut StructName, null on Err (the error value is dropped)
+        return quote! {
+            #[no_mangle]
+            pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
+                match #call {
+                    Ok(obj) => #boxed,
+                    Err(_) => std::ptr::null_mut(),
+                }
+            }
+        };
+    }
+
     if is_constructor {
         // Constructor: returns *mut StructName
-        quote! {
+        return quote! {
             #[no_mangle]
             pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
-                let obj = #struct_name::#method_name(#(#call_args),*);
-                Box::into_raw(Box::new(obj))
+                let obj = #call;
+                #boxed
+            }
+        };
+    }
+
+    match return_type {
+        ReturnType::Default => {
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) {
+                    #self_handling
+                    #call;
+                }
             }
         }
-    } else if is_static {
-        // Static method
-        match return_type {
-            ReturnType::Default => {
-                quote! {
-                    #[no_mangle]
-                    pub extern "C" fn #wrapper_name(#(#wrapper_args),*) {
-                        #struct_name::#method_name(#(#call_args),*);
+        ReturnType::Type(_, ty) if is_self_type(ty, struct_name) => {
+            // Returns Self, box it
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
+                    #self_handling
+                    let obj = #call;
+                    #boxed
+                }
+            }
+        }
+        ReturnType::Type(_, ty)
+            if extract_option_type(ty)
+                .is_some_and(|info| is_self_type(&info.inner_type, struct_name)) =>
+        {
+            // Returns Option<Self>: box Some, null for None
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
+                    #self_handling
+                    match #call {
+                        Some(obj) => #boxed,
+                        None => std::ptr::null_mut(),
                     }
                 }
             }
-            ReturnType::Type(_, ty) => {
-                if is_self_type(ty, struct_name) {
-                    // Returns Self, box it
-                    quote! {
-                        #[no_mangle]
-                        pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
-                            let obj = #struct_name::#method_name(#(#call_args),*);
-                            Box::into_raw(Box::new(obj))
-                        }
+        }
+        ReturnType::Type(_, ty) if is_static_str_type(ty) => {
+            // Returns &'static str: hand out an interned, never-freed C string
+            let interned = generate_static_str_interning(quote! { #call });
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *const std::ffi::c_char {
+                    #self_handling
+                    #interned
+                }
+            }
+        }
+        ReturnType::Type(_, ty)
+            if extract_vec_elem_type(ty).is_some_and(|elem| is_self_type(elem, struct_name)) =>
+        {
+            // Returns Vec<Self>: box each element and hand over a buffer of the pointers
+            let vec_type_name = format_ident!("CVec_{}", wrapper_name);
+            let free_fn_name = format_ident!("CVec_{}_free", wrapper_name);
+            quote! {
+                #[repr(C)]
+                pub struct #vec_type_name {
+                    pub ptr: *mut *mut std::ffi::c_void,
+                    pub len: usize,
+                    pub cap: usize,
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> #vec_type_name {
+                    #self_handling
+                    let items: Vec<*mut std::ffi::c_void> = #call
+                        .into_iter()
+                        .map(|obj| {
+                            let ptr: *mut #struct_name = #boxed;
+                            ptr as *mut std::ffi::c_void
+                        })
+                        .collect();
+                    let mut value = std::mem::ManuallyDrop::new(items);
+                    #vec_type_name {
+                        ptr: value.as_mut_ptr(),
+                        len: value.len(),
+                        cap: value.capacity(),
+                    }
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #free_fn_name(vec: #vec_type_name) {
+                    if !vec.ptr.is_null() {
+                        unsafe { drop(Vec::from_raw_parts(vec.ptr, vec.len, vec.cap)); }
+                    }
+                }
+            }
+        }
+        ReturnType::Type(_, ty) if extract_vec_type(ty).is_some() => {
+            // Returns Vec<T>: hand the buffer over in the rust_helpers CVec layout, freed by
+            // this crate since it owns the allocation
+            let elem_type = extract_vec_type(ty).expect("checked by the match guard");
+            let vec_type_name = format_ident!("CVec_{}", wrapper_name);
+            let free_fn_name = format_ident!("CVec_{}_free", wrapper_name);
+            quote! {
+                #[repr(C)]
+                pub struct #vec_type_name {
+                    pub ptr: *mut #elem_type,
+                    pub len: usize,
+                    pub cap: usize,
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> #vec_type_name {
+                    #self_handling
+                    let mut value = std::mem::ManuallyDrop::new(#call);
+                    #vec_type_name {
+                        ptr: value.as_mut_ptr(),
+                        len: value.len(),
+                        cap: value.capacity(),
                     }
-                } else {
-                    quote! {
-                        #[no_mangle]
-                        pub extern "C" fn #wrapper_name(#(#wrapper_args),*) #return_type {
-                            #struct_name::#method_name(#(#call_args),*)
-                        }
+                }
+
+                #[no_mangle]
+                pub extern "C" fn #free_fn_name(vec: #vec_type_name) {
+                    if !vec.ptr.is_null() {
+                        unsafe { drop(Vec::from_raw_parts(vec.ptr, vec.len, vec.cap)); }
                     }
                 }
             }
         }
-    } else {
-        // Instance method
-        match return_type {
-            ReturnType::Default => {
-                quote! {
-                    #[no_mangle]
-                    pub extern "C" fn #wrapper_name(#(#wrapper_args),*) {
-                        #self_handling
-                        self_ref.#method_name(#(#call_args),*);
-                    }
+        ReturnType::Type(_, ty) if matches!(ty.as_ref(), Type::Reference(_)) => {
+            // A borrow has no lifetime once it crosses the C ABI, so it would dangle
+            quote! {
+                compile_error!(concat!(
+                    "#[julia] method `", stringify!(#struct_name), "::", stringify!(#method_name),
+                    "` returns a reference, which cannot cross the FFI boundary safely. ",
+                    "Return an owned value or a raw pointer instead."
+                ));
+            }
+        }
+        ReturnType::Type(_, _) => {
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn #wrapper_name(#(#wrapper_args),*) #return_type {
+                    #self_handling
+                    #call
                 }
             }
-            ReturnType::Type(_, ty) => {
-                if is_self_type(ty, struct_name) {
-                    // Returns Self, box it
-                    quote! {
-                        #[no_mangle]
-                        pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
-                            #self_handling
-                            let obj = self_ref.#method_name(#(#call_args),*);
-                            Box::into_raw(Box::new(obj))
-                        }
-                    }
-                } else {
-                    quote! {
-                        #[no_mangle]
-                        pub extern "C" fn #wrapper_name(#(#wrapper_args),*) #return_type {
-                            #self_handling
-                            self_ref.#method_name(#(#call_args),*)
-                        }
-                    }
+        }
+    }
+}
+
+/// Generate a block turning the `Box<T>` expression `boxed` into a raw pointer for Julia
+///
+/// Structs with `#[julia(track_frees)]` define an inherent `__julia_track_alloc`, which
+/// takes precedence over the no-op fallback trait below and registers the pointer.
+fn generate_tracked_box(self_ty: &TokenStream2, boxed: TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            trait __JuliaUntracked {
+                fn __julia_track_alloc(ptr: *mut Self) -> *mut Self {
+                    ptr
                 }
             }
+            impl<T> __JuliaUntracked for T {}
+            <#self_ty>::__julia_track_alloc(Box::into_raw(#boxed))
+        }
+    }
+}
+
+/// Check if a type is `&'static str`
+fn is_static_str_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => {
+            reference.mutability.is_none()
+                && reference
+                    .lifetime
+                    .as_ref()
+                    .is_some_and(|lifetime| lifetime.ident == "static")
+                && matches!(reference.elem.as_ref(), Type::Path(p) if p.path.is_ident("str"))
+        }
+        _ => false,
+    }
+}
+
+/// Generate a block converting a `&'static str` expression into `*const c_char`
+///
+/// Each distinct static string is copied into a NUL-terminated `CStr` once and
+/// leaked, so repeated calls don't allocate and Julia never frees the result.
+/// The leak is bounded by the number of distinct static strings in the binary.
+fn generate_static_str_interning(value: TokenStream2) -> TokenStream2 {
+    quote! {
+        {
+            static INTERNED: std::sync::OnceLock<
+                std::sync::Mutex<std::collections::HashMap<(usize, usize), &'static std::ffi::CStr>>,
+            > = std::sync::OnceLock::new();
+
+            let text: &'static str = #value;
+            let key = (text.as_ptr() as usize, text.len());
+            let mut interned = INTERNED
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            interned
+                .entry(key)
+                .or_insert_with(|| {
+                    // Interior NUL bytes cannot be represented in a C string
+                    let c_string = std::ffi::CString::new(text.replace('\0', "")).unwrap_or_default();
+                    Box::leak(c_string.into_boxed_c_str())
+                })
+                .as_ptr()
         }
     }
 }
@@ -747,6 +4528,132 @@ fn is_self_type(ty: &Type, struct_name: &Ident) -> bool {
     }
 }
 
+// ============================================================================
+// julia_canonical!(...) - Shared CResult/COption types for #[julia(canonical)]
+// ============================================================================
+
+/// Declare the shared `CResult_*`/`COption_*` types used by `#[julia(canonical)]` functions.
+///
+/// Takes a comma-separated list of `Result<T, E>` and `Option<T>` types and defines
+/// `CResult_<T>_<E>` / `COption_<T>` for each. Declare every type once, where all functions
+/// using it can see it; a `#[julia(canonical)]` function whose type is not declared fails
+/// with "cannot find type `CResult_...`".
+///
+/// ```rust,ignore
+/// juliacall_macros::julia_canonical!(Result<f64, i32>, Option<f64>);
+///
+/// #[julia(canonical)]
+/// fn checked_sqrt(x: f64) -> Result<f64, i32> {
+///     if x < 0.0 { Err(-1) } else { Ok(x.sqrt()) }
+/// }
+/// ```
+#[proc_macro]
+pub fn julia_canonical(input: TokenStream) -> TokenStream {
+    let parser = syn::punctuated::Punctuated::<Type, syn::Token![,]>::parse_terminated;
+    let types = match syn::parse::Parser::parse(parser, input) {
+        Ok(types) => types,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let mut expanded = TokenStream2::new();
+    for ty in &types {
+        if let Some(result_info) = extract_result_type(ty) {
+            let suffix = canonical_result_suffix(&result_info.ok_type, &result_info.err_type);
+            expanded.extend(generate_c_result_type(
+                &suffix,
+                &result_info.ok_type,
+                &result_info.err_type,
+            ));
+        } else if let Some(option_info) = extract_option_type(ty) {
+            let suffix = canonical_option_suffix(&option_info.inner_type);
+            expanded.extend(generate_c_option_type(&suffix, &option_info.inner_type));
+        } else {
+            expanded.extend(
+                syn::Error::new_spanned(
+                    ty,
+                    "julia_canonical! takes Result<T, E> and Option<T> types",
+                )
+                .to_compile_error(),
+            );
+        }
+    }
+    expanded.into()
+}
+
+// ============================================================================
+// julia_errno!() - Crate-wide error state for #[julia(errno)] functions
+// ============================================================================
+
+/// Declare the thread-local error state used by `#[julia(errno)]` functions.
+///
+/// Invoke once at the crate root. It generates:
+/// - `<crate>_last_error() -> i32` - Code of the last error on this thread (0 if none)
+/// - `<crate>_last_error_message() -> *const c_char` - Message of the last error
+///   (null if none). The string is owned by Rust and stays valid until the next
+///   `#[julia(errno)]` call on the same thread; do not free it.
+///
+/// ```rust,ignore
+/// juliacall_macros::julia_errno!();
+///
+/// #[julia(errno)]
+/// fn checked_sqrt(x: f64) -> Result<f64, i32> {
+///     if x < 0.0 { Err(22) } else { Ok(x.sqrt()) }
+/// }
+/// ```
+#[proc_macro]
+pub fn julia_errno(input: TokenStream) -> TokenStream {
+    if !input.is_empty() {
+        return quote! {
+            compile_error!("julia_errno!() takes no arguments");
+        }
+        .into();
+    }
+
+    let crate_name = std::env::var("CARGO_CRATE_NAME").unwrap_or_else(|_| "crate".to_string());
+    let last_error_fn = format_ident!("{}_last_error", crate_name);
+    let last_error_message_fn = format_ident!("{}_last_error_message", crate_name);
+
+    quote! {
+        #[doc(hidden)]
+        pub mod __julia_errno {
+            use std::cell::{Cell, RefCell};
+            use std::ffi::CString;
+
+            thread_local! {
+                pub(crate) static LAST_ERROR: Cell<i32> = const { Cell::new(0) };
+                pub(crate) static LAST_ERROR_MESSAGE: RefCell<Option<CString>> = const { RefCell::new(None) };
+            }
+
+            pub(crate) fn set(code: i32, message: String) {
+                // Interior NUL bytes cannot be represented in a C string
+                let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+                LAST_ERROR.with(|c| c.set(code));
+                LAST_ERROR_MESSAGE.with(|m| *m.borrow_mut() = Some(message));
+            }
+
+            pub(crate) fn clear() {
+                LAST_ERROR.with(|c| c.set(0));
+                LAST_ERROR_MESSAGE.with(|m| *m.borrow_mut() = None);
+            }
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #last_error_fn() -> i32 {
+            __julia_errno::LAST_ERROR.with(|c| c.get())
+        }
+
+        #[no_mangle]
+        pub extern "C" fn #last_error_message_fn() -> *const std::ffi::c_char {
+            __julia_errno::LAST_ERROR_MESSAGE.with(|m| {
+                m.borrow()
+                    .as_ref()
+                    .map_or(std::ptr::null(), |message| message.as_ptr())
+            })
+        }
+    }
+    .into()
+}
+
 // ============================================================================
 // #[julia_pyo3] - Unified macro for Julia + Python bindings
 // ============================================================================
@@ -783,6 +4690,11 @@ fn is_self_type(ty: &Type, struct_name: &Ident) -> bool {
 /// - Julia: `#[repr(C)]` struct + FFI functions (Point_free, Point_get_x, etc.)
 /// - Python (with feature): `#[pyclass(get_all, set_all)]`
 ///
+/// Place `#[derive(Debug)]` after `#[julia_pyo3]` to get a Python `__repr__` using the
+/// `{:?}` formatting; other structs keep PyO3's default repr. PyO3 allows one `#[pymethods]`
+/// block per class, so the `__repr__` is added to the block of the struct's `#[julia_pyo3]`
+/// impl: a Debug struct without one needs an empty `#[julia_pyo3] impl Point {}`.
+///
 /// # For Impl Blocks
 ///
 /// ```rust,ignore
@@ -796,6 +4708,14 @@ fn is_self_type(ty: &Type, struct_name: &Ident) -> bool {
 /// This generates:
 /// - Julia: FFI wrapper functions (Point_new, Point_distance)
 /// - Python (with feature): `#[pymethods]` impl block with `#[new]` for constructors
+///
+/// The impl block must follow its `#[julia_pyo3]` struct, which defines the hidden
+/// `__julia_pyo3_pymethods_<Struct>!` macro that emits the `#[pymethods]` block.
+///
+/// A fallible constructor `fn new(...) -> Result<Self, E>` returns null to Julia on `Err`,
+/// dropping the error, while the `#[new]` method keeps the `Result` so Python raises it.
+/// PyO3 requires `E: Into<PyErr>` (e.g. `PyErr` from `PyValueError::new_err`); a `String`
+/// error compiles for Julia but not for Python.
 #[proc_macro_attribute]
 pub fn julia_pyo3(_attr: TokenStream, item: TokenStream) -> TokenStream {
     // Try to parse as a function first
@@ -849,6 +4769,69 @@ fn transform_function_julia_pyo3(func: ItemFn) -> TokenStream2 {
         }
     }
 
+    // `&str` parameters and `String` returns aren't FFI-safe: the Julia version takes
+    // `*const c_char` and returns an owned `*mut c_char` (freed with `<fn>_free`), while
+    // PyO3 keeps the Rust types
+    let returns_string = matches!(&func.sig.output, ReturnType::Type(_, ty) if is_string_type(ty));
+    if returns_string || func.sig.inputs.iter().any(|arg| str_param(arg).is_some()) {
+        let func_name = &func.sig.ident;
+        let lowered = lower_params(&func.sig.inputs);
+        let args = &lowered.params;
+        let prelude = &lowered.prelude;
+        let arg_names = collect_arg_names(&func);
+        let inner_fn_name = format_ident!("{}_inner", func_name);
+        let inner_fn_args = &func.sig.inputs;
+        let inner_fn_output = &func.sig.output;
+        let inner_fn_attrs = forwarded_attrs(&func);
+        let (ret, call, free_fn) = if returns_string {
+            let free_fn_name = format_ident!("{}_free", func_name);
+            (
+                quote! { -> *mut std::ffi::c_char },
+                quote! {
+                    // Interior NUL bytes cannot be represented in a C string
+                    std::ffi::CString::new(#inner_fn_name(#(#arg_names),*).replace('\0', ""))
+                        .unwrap_or_default()
+                        .into_raw()
+                },
+                quote! {
+                    #[cfg(not(feature = "python"))]
+                    #[no_mangle]
+                    pub extern "C" fn #free_fn_name(text: *mut std::ffi::c_char) {
+                        if !text.is_null() {
+                            unsafe { drop(std::ffi::CString::from_raw(text)); }
+                        }
+                    }
+                },
+            )
+        } else {
+            (
+                quote! { #inner_fn_output },
+                quote! { #inner_fn_name(#(#arg_names),*) },
+                TokenStream2::new(),
+            )
+        };
+        return quote! {
+            // Julia FFI version (when python feature is OFF)
+            #[cfg(not(feature = "python"))]
+            #(#inner_fn_attrs)*
+            fn #inner_fn_name(#inner_fn_args) #inner_fn_output #func_block
+
+            #[cfg(not(feature = "python"))]
+            #[no_mangle]
+            pub extern "C" fn #func_name(#(#args),*) #ret {
+                #prelude
+                #call
+            }
+
+            #free_fn
+
+            // Python version (when python feature is ON)
+            #[cfg(feature = "python")]
+            #[pyo3::pyfunction]
+            pub #func_sig #func_block
+        };
+    }
+
     // For simple types, generate both versions with cfg
     quote! {
         // Julia FFI version (when python feature is OFF)
@@ -929,15 +4912,61 @@ fn transform_struct_julia_pyo3(mut item_struct: ItemStruct) -> TokenStream2 {
         }
     }
 
+    let pymethods_macro =
+        generate_pyo3_pymethods_macro(struct_name, has_derive(&item_struct.attrs, "Debug"));
+
     // Generate output with conditional PyO3 attributes
     quote! {
         #[cfg_attr(feature = "python", pyo3::pyclass(get_all, set_all))]
         #item_struct
 
+        #pymethods_macro
+
         #ffi_functions
     }
 }
 
+/// Generate the hidden `__julia_pyo3_pymethods_<Struct>!` macro used by `#[julia_pyo3]` impls
+///
+/// PyO3 allows a single `#[pymethods]` block per class (without `multiple-pymethods`), so
+/// the impl block hands its methods to this macro, which emits the block and adds a
+/// `__repr__` using `{:?}` when the struct derives `Debug`. Other structs keep PyO3's repr.
+fn generate_pyo3_pymethods_macro(struct_name: &Ident, debug: bool) -> TokenStream2 {
+    let macro_name = format_ident!("__julia_pyo3_pymethods_{}", struct_name);
+    let repr = if debug {
+        quote! {
+            fn __repr__(&self) -> String {
+                format!("{:?}", self)
+            }
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(unused_macros)]
+        macro_rules! #macro_name {
+            // The impl block defines its own __repr__
+            (methods { $($methods:tt)* }) => {
+                #[cfg(feature = "python")]
+                #[pyo3::pymethods]
+                impl #struct_name {
+                    $($methods)*
+                }
+            };
+            (methods { $($methods:tt)* } repr) => {
+                #[cfg(feature = "python")]
+                #[pyo3::pymethods]
+                impl #struct_name {
+                    $($methods)*
+                    #repr
+                }
+            };
+        }
+    }
+}
+
 /// Transform an impl block with #[julia_pyo3] attribute
 fn transform_impl_julia_pyo3(item_impl: ItemImpl) -> TokenStream2 {
     let self_ty = &item_impl.self_ty;
@@ -973,6 +5002,15 @@ fn transform_impl_julia_pyo3(item_impl: ItemImpl) -> TokenStream2 {
         }
     }
 
+    // The struct's macro emits the single #[pymethods] block, adding a Debug __repr__
+    // unless the impl block defines one
+    let pymethods_macro = format_ident!("__julia_pyo3_pymethods_{}", struct_name);
+    let has_user_repr = item_impl
+        .items
+        .iter()
+        .any(|item| matches!(item, syn::ImplItem::Fn(method) if method.sig.ident == "__repr__"));
+    let repr = (!has_user_repr).then(|| quote! { repr });
+
     // Output:
     // 1. Original impl block when python feature is OFF
     // 2. #[pymethods] impl block when python feature is ON
@@ -983,11 +5021,7 @@ fn transform_impl_julia_pyo3(item_impl: ItemImpl) -> TokenStream2 {
         #[cfg(not(feature = "python"))]
         #item_impl
 
-        #[cfg(feature = "python")]
-        #[pyo3::pymethods]
-        impl #struct_name {
-            #pyo3_methods
-        }
+        #pymethods_macro! { methods { #pyo3_methods } #repr }
 
         #julia_ffi_wrappers
     }
@@ -1083,7 +5117,27 @@ fn generate_method_wrapper_pyo3(struct_name: &Ident, method: &syn::ImplItemFn) -
     // Determine return type handling
     let return_type = &method.sig.output;
 
-    if is_constructor {
+    // Fallible constructor: a static method returning Result<Self, E>
+    let is_fallible_constructor = is_static
+        && matches!(
+            return_type,
+            ReturnType::Type(_, ty) if extract_result_type(ty)
+                .is_some_and(|result| is_self_type(&result.ok_type, struct_name))
+        );
+
+    if is_fallible_constructor {
+        // Julia gets null on Err and the error is dropped; the #[pymethods] copy keeps the
+        // Result so Python raises it (PyO3 requires E: Into<PyErr>)
+        quote! {
+            #[no_mangle]
+            pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut #struct_name {
+                match #struct_name::#method_name(#(#call_args),*) {
+                    Ok(obj) => Box::into_raw(Box::new(obj)),
+                    Err(_) => std::ptr::null_mut(),
+                }
+            }
+        }
+    } else if is_constructor {
         // Constructor: static method that returns Self, returns *mut StructName
         quote! {
             #[no_mangle]
@@ -1148,3 +5202,476 @@ fn generate_method_wrapper_pyo3(struct_name: &Ident, method: &syn::ImplItemFn) -
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Find the generated function named `name`
+    fn find_fn<'a>(file: &'a syn::File, name: &str) -> &'a ItemFn {
+        file.items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Fn(func) if func.sig.ident == name => Some(func),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("`{name}` should be generated"))
+    }
+
+    /// Find the generated `#[pymethods]` impl block
+    /// The methods a `#[julia_pyo3]` impl passes to `__julia_pyo3_pymethods_<Struct>!`,
+    /// and whether it asks for the Debug `__repr__`
+    fn find_pymethods(file: &syn::File) -> (ItemImpl, bool) {
+        let mac = file
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Macro(item_macro)
+                    if item_macro.mac.path.segments.last().is_some_and(|s| {
+                        s.ident.to_string().starts_with("__julia_pyo3_pymethods_")
+                    }) =>
+                {
+                    Some(&item_macro.mac)
+                }
+                _ => None,
+            })
+            .expect("__julia_pyo3_pymethods_<Struct>! invocation should be generated");
+        let tokens: Vec<proc_macro2::TokenTree> = mac.tokens.clone().into_iter().collect();
+        let proc_macro2::TokenTree::Group(methods) = &tokens[1] else {
+            panic!("expected `methods {{ ... }}`");
+        };
+        let item_impl = syn::parse2(quote!(impl Sample #methods)).expect("methods should parse");
+        (item_impl, tokens.len() == 3)
+    }
+
+    #[test]
+    fn pyo3_fallible_constructor_returns_null_for_julia() {
+        let item_impl: ItemImpl = syn::parse_quote! {
+            impl Account {
+                pub fn new(balance: i64) -> Result<Self, pyo3::PyErr> {
+                    if balance < 0 {
+                        Err(pyo3::exceptions::PyValueError::new_err("negative balance"))
+                    } else {
+                        Ok(Account { balance })
+                    }
+                }
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(transform_impl_julia_pyo3(item_impl))
+            .expect("expansion should parse");
+
+        // Julia FFI wrapper: *mut Account, null on Err
+        let wrapper = find_fn(&expanded, "Account_new");
+        let wrapper_output = &wrapper.sig.output;
+        assert_eq!(
+            quote!(#wrapper_output).to_string(),
+            quote!(-> *mut Account).to_string()
+        );
+        let wrapper_body = &wrapper.block;
+        assert!(quote!(#wrapper_body).to_string().contains("null_mut"));
+
+        // Python: #[new] keeps the Result so PyO3 raises on Err
+        let (pymethods, _) = find_pymethods(&expanded);
+        let syn::ImplItem::Fn(constructor) = &pymethods.items[0] else {
+            panic!("expected the constructor in #[pymethods]");
+        };
+        assert!(constructor
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("new")));
+        assert!(
+            matches!(&constructor.sig.output, ReturnType::Type(_, ty) if extract_result_type(ty).is_some())
+        );
+    }
+
+    #[test]
+    fn pyo3_str_params_lower_to_c_strings_for_julia_only() {
+        let func: ItemFn = syn::parse_quote! {
+            fn shout(s: &str) -> String {
+                s.to_uppercase()
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(transform_function_julia_pyo3(func))
+            .expect("expansion should parse");
+        let is_python = |func: &&ItemFn| {
+            func.attrs.iter().any(|attr| {
+                attr.path()
+                    .segments
+                    .last()
+                    .is_some_and(|s| s.ident == "pyfunction")
+            })
+        };
+        let shouts: Vec<&ItemFn> = expanded
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) if func.sig.ident == "shout" => Some(func),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(shouts.len(), 2);
+
+        // Julia FFI wrapper: NUL-terminated string in, owned C string out
+        let julia = shouts.iter().find(|f| !is_python(f)).unwrap();
+        let julia_inputs = &julia.sig.inputs;
+        assert_eq!(
+            quote!(#julia_inputs).to_string(),
+            quote!(s: *const std::ffi::c_char).to_string()
+        );
+        let julia_output = &julia.sig.output;
+        assert_eq!(
+            quote!(#julia_output).to_string(),
+            quote!(-> *mut std::ffi::c_char).to_string()
+        );
+        // The returned string is freed by the same crate that allocated it
+        find_fn(&expanded, "shout_free");
+
+        // Python: PyO3 converts &str and String itself
+        let python = shouts.iter().find(|f| is_python(f)).unwrap();
+        let python_inputs = &python.sig.inputs;
+        assert_eq!(
+            quote!(#python_inputs).to_string(),
+            quote!(s: &str).to_string()
+        );
+        let python_output = &python.sig.output;
+        assert_eq!(
+            quote!(#python_output).to_string(),
+            quote!(-> String).to_string()
+        );
+    }
+
+    #[test]
+    fn canonical_type_names_are_identifiers() {
+        let cases: [(Type, &str); 4] = [
+            (syn::parse_quote!(f64), "f64"),
+            (syn::parse_quote!(*mut Point), "ptr_mut_Point"),
+            (syn::parse_quote!([u8; 4]), "u8_4"),
+            (syn::parse_quote!(std::os::raw::c_int), "std_os_raw_c_int"),
+        ];
+        for (ty, expected) in cases {
+            assert_eq!(mangle_type_name(&ty), expected);
+        }
+    }
+
+    #[test]
+    fn pyo3_repr_only_for_debug_structs() {
+        // The struct's pymethods macro adds __repr__ only when the struct derives Debug
+        let defines_repr = |item_struct: ItemStruct| {
+            let expanded = syn::parse2::<syn::File>(transform_struct_julia_pyo3(item_struct))
+                .expect("expansion should parse");
+            expanded
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    syn::Item::Macro(item_macro)
+                        if item_macro.ident.as_ref().is_some_and(|ident| {
+                            ident.to_string().starts_with("__julia_pyo3_pymethods_")
+                        }) =>
+                    {
+                        Some(item_macro.mac.tokens.to_string().contains("__repr__"))
+                    }
+                    _ => None,
+                })
+                .expect("pymethods macro should be generated")
+        };
+        assert!(defines_repr(syn::parse_quote! {
+            #[derive(Debug)]
+            pub struct Sample {
+                pub value: i32,
+            }
+        }));
+        assert!(!defines_repr(syn::parse_quote! {
+            pub struct Plain {
+                pub value: i32,
+            }
+        }));
+
+        // The impl block asks for it unless it defines its own __repr__
+        let requests_repr = |item_impl: ItemImpl| {
+            let expanded = syn::parse2::<syn::File>(transform_impl_julia_pyo3(item_impl))
+                .expect("expansion should parse");
+            find_pymethods(&expanded).1
+        };
+        assert!(requests_repr(syn::parse_quote! {
+            impl Sample {}
+        }));
+        assert!(!requests_repr(syn::parse_quote! {
+            impl Sample {
+                pub fn __repr__(&self) -> String {
+                    String::from("Sample")
+                }
+            }
+        }));
+    }
+
+    #[test]
+    fn no_free_struct_has_no_free_function() {
+        let exported = |item_struct: ItemStruct, options: &JuliaOptions| -> Vec<String> {
+            let expanded = syn::parse2::<syn::File>(transform_struct(item_struct, options))
+                .expect("expansion should parse");
+            expanded
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::Item::Fn(func) => Some(func.sig.ident.to_string()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let item_struct: ItemStruct = syn::parse_quote! {
+            pub struct Registry {
+                pub count: u32,
+            }
+        };
+
+        let owned = exported(item_struct.clone(), &JuliaOptions::default());
+        assert!(owned.iter().any(|name| name == "Registry_free"));
+
+        let options = JuliaOptions {
+            no_free: true,
+            ..JuliaOptions::default()
+        };
+        let borrowed = exported(item_struct, &options);
+        assert!(!borrowed.iter().any(|name| name == "Registry_free"));
+        assert!(borrowed.iter().any(|name| name == "Registry_get_count"));
+    }
+
+    #[test]
+    fn inner_function_keeps_user_attributes() {
+        let func: ItemFn = syn::parse_quote! {
+            #[inline]
+            #[cold]
+            fn checked_half(x: i32) -> Result<i32, i32> {
+                if x % 2 == 0 { Ok(x / 2) } else { Err(x) }
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(transform_function(func, &JuliaOptions::default()))
+            .expect("expansion should parse");
+        let inner = find_fn(&expanded, "checked_half_inner");
+        assert!(inner
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("inline")));
+        assert!(inner.attrs.iter().any(|attr| attr.path().is_ident("cold")));
+    }
+
+    #[test]
+    fn cfg_feature_gates_every_generated_item() {
+        let item_struct: ItemStruct = syn::parse_quote! {
+            pub struct Gated {
+                pub value: f64,
+            }
+        };
+        let feature: LitStr = syn::parse_quote!("extra");
+        let expanded = transform_struct(item_struct, &JuliaOptions::default());
+        let gated = syn::parse2::<syn::File>(apply_cfg_feature(expanded, &feature))
+            .expect("expansion should parse");
+
+        let cfg_attr: Attribute = syn::parse_quote!(#[cfg(feature = "extra")]);
+        assert!(gated.items.len() > 3);
+        for item in &gated.items {
+            let attrs = match item {
+                syn::Item::Struct(item) => &item.attrs,
+                syn::Item::Fn(item) => &item.attrs,
+                syn::Item::Macro(item) => &item.attrs,
+                syn::Item::Impl(item) => &item.attrs,
+                other => panic!("unexpected item: {}", quote!(#other)),
+            };
+            assert_eq!(attrs.first(), Some(&cfg_attr));
+        }
+    }
+
+    #[test]
+    fn abi_option_replaces_extern_c() {
+        let func: ItemFn = syn::parse_quote! {
+            fn halve(x: i32) -> Option<i32> {
+                (x % 2 == 0).then_some(x / 2)
+            }
+        };
+        let abi: LitStr = syn::parse_quote!("C-unwind");
+        let expanded = apply_abi(transform_function(func, &JuliaOptions::default()), &abi);
+        let file = syn::parse2::<syn::File>(expanded).expect("expansion should parse");
+
+        let wrapper = find_fn(&file, "halve");
+        let wrapper_abi = wrapper.sig.abi.as_ref().and_then(|abi| abi.name.as_ref());
+        assert_eq!(wrapper_abi.map(LitStr::value).as_deref(), Some("C-unwind"));
+
+        // The inner function keeps the Rust ABI
+        assert!(find_fn(&file, "halve_inner").sig.abi.is_none());
+    }
+
+    #[test]
+    fn slice_params_keep_their_position_among_scalars() {
+        let func: ItemFn = syn::parse_quote! {
+            fn scaled(base: i32, xs: &[i32], scale: i32) -> Option<i32> {
+                Some(base + xs.len() as i32 * scale)
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(transform_function(func, &JuliaOptions::default()))
+            .expect("expansion should parse");
+        let wrapper = find_fn(&expanded, "scaled");
+        let names: Vec<String> = wrapper
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                    Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect();
+        assert_eq!(names, ["base", "xs_ptr", "xs_len", "scale"]);
+        let body = &wrapper.block;
+        assert!(quote!(#body)
+            .to_string()
+            .contains(&quote!(scaled_inner(base, xs, scale)).to_string()));
+    }
+
+    #[test]
+    fn restricted_visibility_items_export_pub_wrappers() {
+        let func: ItemFn = syn::parse_quote! {
+            pub(crate) fn half(x: i32) -> Option<i32> {
+                (x % 2 == 0).then_some(x / 2)
+            }
+        };
+        let item_impl: ItemImpl = syn::parse_quote! {
+            impl Gauge {
+                #[julia]
+                pub(crate) fn level(&self) -> f64 {
+                    self.level
+                }
+            }
+        };
+        let mut expanded =
+            syn::parse2::<syn::File>(transform_function(func, &JuliaOptions::default()))
+                .expect("function expansion should parse");
+        expanded.items.extend(
+            syn::parse2::<syn::File>(transform_impl(item_impl, &JuliaOptions::default()))
+                .expect("impl expansion should parse")
+                .items,
+        );
+
+        let mut exported = Vec::new();
+        for item in &expanded.items {
+            if let syn::Item::Fn(func) = item {
+                if func
+                    .attrs
+                    .iter()
+                    .any(|attr| attr.path().is_ident("no_mangle"))
+                {
+                    assert!(
+                        matches!(func.vis, Visibility::Public(_)),
+                        "{} is not pub",
+                        func.sig.ident
+                    );
+                    exported.push(func.sig.ident.to_string());
+                } else {
+                    // Inner helpers stay private so only the wrapper is exported
+                    assert!(
+                        matches!(func.vis, Visibility::Inherited),
+                        "{} leaks",
+                        func.sig.ident
+                    );
+                }
+            }
+        }
+        assert_eq!(exported, ["half", "Gauge_level"]);
+    }
+
+    #[test]
+    fn link_name_replaces_no_mangle_on_the_wrapper_only() {
+        let options = parse_julia_options(quote!(link_name = "rc_ratio")).expect("valid options");
+        let item = quote! {
+            fn ratio(a: f64, b: f64) -> Result<f64, String> {
+                if b == 0.0 { Err("division by zero".to_string()) } else { Ok(a / b) }
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(expand_julia_item(item, &options))
+            .expect("expansion should parse");
+        let exports: Vec<(String, String)> = expanded
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) => func.attrs.iter().find_map(|attr| {
+                    let kind = attr.path().get_ident()?.to_string();
+                    let symbol = match &attr.meta {
+                        syn::Meta::NameValue(name_value) => {
+                            let value = &name_value.value;
+                            quote!(#value).to_string()
+                        }
+                        _ => func.sig.ident.to_string(),
+                    };
+                    matches!(kind.as_str(), "no_mangle" | "export_name").then_some((kind, symbol))
+                }),
+                _ => None,
+            })
+            .collect();
+        assert!(exports.contains(&("export_name".to_string(), "\"rc_ratio\"".to_string())));
+        assert!(exports.contains(&("no_mangle".to_string(), "ratio_last_error".to_string())));
+        assert!(!exports.contains(&("no_mangle".to_string(), "ratio".to_string())));
+    }
+
+    #[test]
+    fn deprecated_marks_the_exported_wrapper_only() {
+        let options =
+            parse_julia_options(quote!(deprecated = "use ratio2")).expect("valid options");
+        let item = quote! {
+            fn ratio(a: f64, b: f64) -> Result<f64, String> {
+                if b == 0.0 { Err("division by zero".to_string()) } else { Ok(a / b) }
+            }
+        };
+        let expanded = syn::parse2::<syn::File>(expand_julia_item(item, &options))
+            .expect("expansion should parse");
+        let deprecated: Vec<(String, bool, String)> = expanded
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) => func.attrs.iter().find_map(|attr| {
+                    attr.path().is_ident("deprecated").then(|| {
+                        let exported = func.attrs.iter().any(|a| a.path().is_ident("no_mangle"));
+                        let meta = &attr.meta;
+                        (
+                            func.sig.ident.to_string(),
+                            exported,
+                            quote!(#meta).to_string(),
+                        )
+                    })
+                }),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deprecated.len(), 1);
+        assert_eq!(deprecated[0].0, "ratio");
+        assert!(deprecated[0].1, "the deprecated wrapper keeps #[no_mangle]");
+        assert!(deprecated[0].2.contains("\"use ratio2\""));
+    }
+
+    #[test]
+    fn box_requires_by_value_or_nested_fields() {
+        let has_box = |item_struct: ItemStruct| {
+            let name = format!("{}_box", item_struct.ident);
+            let expanded =
+                syn::parse2::<syn::File>(transform_struct(item_struct, &JuliaOptions::default()))
+                    .expect("expansion should parse");
+            expanded
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(func) if func.sig.ident == name))
+        };
+        assert!(!has_box(syn::parse_quote! {
+            pub struct Stopwatch {
+                pub elapsed: std::time::Duration,
+                pub laps: u32,
+            }
+        }));
+        assert!(has_box(syn::parse_quote! {
+            pub struct Circle {
+                #[julia(nested)]
+                pub center: Point,
+                pub radius: f64,
+            }
+        }));
+    }
+}
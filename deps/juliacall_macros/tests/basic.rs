@@ -66,6 +66,7 @@ fn find_first_positive(a: i32, b: i32) -> Option<i32> {
 
 // Test that #[julia] on structs compiles correctly
 #[julia]
+#[derive(Debug, Clone)]
 pub struct TestPoint {
     pub x: f64,
     pub y: f64,
@@ -143,6 +144,973 @@ pub extern "C" fn Builder_free(ptr: *mut Builder) {
     }
 }
 
+// ============================================================================
+// Enum tests (explicit discriminants)
+// ============================================================================
+
+#[julia(repr = "i32")]
+pub enum Color {
+    Red = 1,
+    Green = 2,
+    Blue = 7,
+}
+
+#[julia(repr = "u8")]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+// ============================================================================
+// Null-checked getter tests (safe_access)
+// ============================================================================
+
+#[julia(safe_access)]
+pub struct SafePoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+// Callbacks have no zero value, so the null-checked getter returns a nullable fn pointer
+#[julia(safe_access)]
+pub struct SafeHook {
+    pub callback: extern "C" fn(i32) -> i32,
+}
+
+// ============================================================================
+// errno-style error reporting tests
+// ============================================================================
+
+juliacall_macros::julia_errno!();
+
+pub struct NegativeInput;
+
+impl std::fmt::Display for NegativeInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input must be non-negative")
+    }
+}
+
+impl From<NegativeInput> for i32 {
+    fn from(_: NegativeInput) -> i32 {
+        22
+    }
+}
+
+#[julia(errno)]
+fn checked_sqrt(x: f64) -> Result<f64, NegativeInput> {
+    if x < 0.0 {
+        Err(NegativeInput)
+    } else {
+        Ok(x.sqrt())
+    }
+}
+
+// ============================================================================
+// Type alias tests
+// ============================================================================
+
+#[julia]
+pub type Vec2 = TestPoint;
+
+// ============================================================================
+// &'static str method return tests
+// ============================================================================
+
+pub struct Shape {
+    sides: u32,
+}
+
+#[julia]
+impl Shape {
+    #[julia]
+    pub fn new(sides: u32) -> Self {
+        Self { sides }
+    }
+
+    #[julia]
+    pub fn kind_name(&self) -> &'static str {
+        match self.sides {
+            3 => "triangle",
+            4 => "square",
+            _ => "polygon",
+        }
+    }
+
+    #[julia]
+    pub fn family() -> &'static str {
+        "shape"
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn Shape_free(ptr: *mut Shape) {
+    if !ptr.is_null() {
+        unsafe {
+            drop(Box::from_raw(ptr));
+        }
+    }
+}
+
+// Test Result<(), E> (no Ok payload)
+#[julia]
+fn commit(n: i32) -> Result<(), i32> {
+    if n >= 0 {
+        Ok(())
+    } else {
+        Err(n)
+    }
+}
+
+// Structs with heap fields still compile outside verify_repr_c
+#[julia]
+pub struct Labeled {
+    pub id: i32,
+    pub payload: Box<i32>,
+}
+
+#[julia(verify_repr_c)]
+pub struct Verified {
+    pub id: i32,
+    pub weight: f64,
+}
+
+// Test Result<T, Box<E>> with a #[julia] error struct
+#[julia]
+pub struct LoadError {
+    pub code: i32,
+    pub line: u32,
+}
+
+#[julia]
+fn load_setting(line: u32) -> Result<f64, Box<LoadError>> {
+    if line == 0 {
+        Err(Box::new(LoadError { code: 404, line }))
+    } else {
+        Ok(line as f64 * 0.5)
+    }
+}
+
+// ============================================================================
+// Slice parameter tests
+// ============================================================================
+
+#[julia]
+fn mean(xs: &[f64]) -> f64 {
+    if xs.is_empty() {
+        0.0
+    } else {
+        xs.iter().sum::<f64>() / xs.len() as f64
+    }
+}
+
+#[julia]
+fn checked_mean(xs: &[f64]) -> Option<f64> {
+    if xs.is_empty() {
+        None
+    } else {
+        Some(xs.iter().sum::<f64>() / xs.len() as f64)
+    }
+}
+
+#[julia(checked)]
+fn axpy(a: f64, x: &[f64], y: &mut [f64]) {
+    for (yi, xi) in y.iter_mut().zip(x) {
+        *yi += a * xi;
+    }
+}
+
+// ============================================================================
+// Scratch buffer tests
+// ============================================================================
+
+#[julia(scratch(f64, 64))]
+fn scratch_sum_squares(n: usize) -> f64 {
+    with_scratch(|buf| {
+        let n = n.min(buf.len());
+        for (i, slot) in buf[..n].iter_mut().enumerate() {
+            *slot = (i * i) as f64;
+        }
+        buf[..n].iter().sum()
+    })
+}
+
+#[julia(scratch(f64, 64))]
+fn scratch_address() -> usize {
+    with_scratch(|buf| buf.as_ptr() as usize)
+}
+
+// ============================================================================
+// Out-parameter tests
+// ============================================================================
+
+#[julia]
+pub struct Transform {
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub shear_x: f64,
+    pub shear_y: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+#[julia(out_param)]
+fn make_transform(scale: f64, dx: f64, dy: f64) -> Transform {
+    Transform {
+        scale_x: scale,
+        scale_y: scale,
+        shear_x: 0.0,
+        shear_y: 0.0,
+        offset_x: dx,
+        offset_y: dy,
+    }
+}
+
+// ============================================================================
+// Nullable pointer field tests
+// ============================================================================
+
+#[julia]
+pub struct TreeNode {
+    pub value: i32,
+    pub parent: *mut TreeNode,
+}
+
+// ============================================================================
+// Field diff tests
+// ============================================================================
+
+#[julia(diff)]
+pub struct Settings {
+    pub width: i32,
+    pub height: i32,
+    pub scale: f64,
+    pub visible: bool,
+}
+
+// ============================================================================
+// Nested struct field tests
+// ============================================================================
+
+#[julia]
+pub struct Circle {
+    #[julia(nested)]
+    pub center: TestPoint,
+    pub radius: f64,
+}
+
+// Unmarked fields of other struct types are left alone: AtomicU32 is not Clone
+#[julia]
+pub struct HitCounter {
+    pub hits: std::sync::atomic::AtomicU32,
+    pub limit: u32,
+}
+
+// Duration is not FFI-safe by value, so Timing gets no Timing_box
+#[julia]
+pub struct Timing {
+    pub elapsed: std::time::Duration,
+    pub laps: u32,
+}
+
+// ============================================================================
+// Result<Vec<T>, String> tests
+// ============================================================================
+
+#[julia]
+fn parse_numbers(text: &[u8]) -> Result<Vec<f64>, String> {
+    let text = std::str::from_utf8(text).map_err(|err| err.to_string())?;
+    text.split(',')
+        .map(|field| {
+            field
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid number: {:?}", field.trim()))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Double-free tracking tests
+// ============================================================================
+
+#[julia(track_frees)]
+pub struct Ticket {
+    pub id: u32,
+}
+
+#[julia]
+impl Ticket {
+    #[julia]
+    pub fn new(id: u32) -> Self {
+        Ticket { id }
+    }
+}
+
+// ============================================================================
+// Box<T> return tests
+// ============================================================================
+
+#[julia]
+fn make_point(x: f64, y: f64) -> Box<TestPoint> {
+    Box::new(TestPoint { x, y })
+}
+
+#[julia]
+fn issue_ticket(id: u32) -> Box<Ticket> {
+    Box::new(Ticket { id })
+}
+
+// ============================================================================
+// Result<T, String> tests
+// ============================================================================
+
+#[julia]
+fn parse_count(text: &[u8]) -> Result<i32, String> {
+    let text = std::str::from_utf8(text).map_err(|err| err.to_string())?;
+    text.trim()
+        .parse::<i32>()
+        .map_err(|err| format!("cannot parse {:?} as a count: {}", text, err))
+}
+
+// ============================================================================
+// Array field tests
+// ============================================================================
+
+#[julia]
+pub struct Vec3 {
+    pub data: [f64; 3],
+}
+
+// ============================================================================
+// no_free tests
+// ============================================================================
+
+#[julia(no_free)]
+pub struct GlobalStats {
+    pub calls: u64,
+}
+
+static mut GLOBAL_STATS: GlobalStats = GlobalStats { calls: 0 };
+
+#[julia]
+fn global_stats() -> *mut GlobalStats {
+    std::ptr::addr_of_mut!(GLOBAL_STATS)
+}
+
+// Test tuple and unit structs
+#[julia]
+pub struct Celsius(pub f64);
+
+#[julia]
+pub struct Marker;
+
+// Enums with a zero discriminant can be returned inside Option
+#[julia]
+fn direction_from_code(code: u8) -> Option<Direction> {
+    match code {
+        0 => Some(Direction::North),
+        3 => Some(Direction::West),
+        _ => None,
+    }
+}
+
+// Fixed-size array returns are wrapped in CArr_<fn>
+#[julia]
+fn first_odds(start: i32) -> [i32; 3] {
+    let first = start | 1;
+    [first, first + 2, first + 4]
+}
+
+// #[julia(all)] wraps every pub method without per-method annotations
+#[julia]
+pub struct Accumulator {
+    pub sum: f64,
+    pub count: u32,
+}
+
+#[julia(all)]
+impl Accumulator {
+    pub fn new(first: f64) -> Self {
+        Self {
+            sum: first,
+            count: 1,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.ratio()
+    }
+
+    #[julia(skip)]
+    pub fn summary(&self) -> String {
+        format!("{} values, sum {}", self.count, self.sum)
+    }
+
+    fn ratio(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum / self.count as f64
+        }
+    }
+}
+
+// Would clash with a generated wrapper if #[julia(skip)] were ignored
+#[allow(non_snake_case)]
+fn Accumulator_summary() -> &'static str {
+    "no wrapper"
+}
+
+// 128-bit fields split into u64 halves
+#[julia(split_128)]
+pub struct Ledger {
+    pub total: u128,
+    pub delta: i128,
+    pub entries: u32,
+}
+
+// safe_access also null-checks the split halves
+#[julia(safe_access, split_128)]
+pub struct SafeLedger {
+    pub total: u128,
+}
+
+// Option<*mut T> returns collapse to a nullable pointer
+#[julia]
+fn new_positive_node(value: i32) -> Option<*mut TreeNode> {
+    (value > 0).then(|| {
+        Box::into_raw(Box::new(TreeNode {
+            value,
+            parent: std::ptr::null_mut(),
+        }))
+    })
+}
+
+// Items gated on a cargo feature (built both with and without `python`)
+#[julia(cfg = "python")]
+pub struct GatedSample {
+    pub value: f64,
+}
+
+#[julia(cfg = "python")]
+fn gated_double(x: f64) -> Option<f64> {
+    x.is_finite().then_some(x * 2.0)
+}
+
+// Methods returning Vec<T> hand the buffer over as a CVec
+#[julia]
+pub struct Triangle {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+#[julia]
+impl Triangle {
+    #[julia]
+    pub fn sides(&self) -> Vec<f64> {
+        vec![self.a, self.b, self.c]
+    }
+
+    #[julia]
+    pub fn rounded_sides(&self) -> Vec<u8> {
+        vec![self.a as u8, self.b as u8, self.c as u8]
+    }
+}
+
+// _set_all updates every FFI-compatible field in one call
+#[julia]
+pub struct Rectangle {
+    pub width: f64,
+    pub height: f64,
+    pub tag: [u8; 4],
+}
+
+// Exported with the "C-unwind" ABI
+#[julia(abi = "C-unwind")]
+fn halve_even(x: i32) -> Option<i32> {
+    (x % 2 == 0).then_some(x / 2)
+}
+
+// Option<primitive> fields get nullable accessors
+#[julia]
+pub struct Node {
+    pub id: Option<i32>,
+}
+
+// Hashable structs get <Struct>_hash
+#[julia]
+#[derive(Hash, PartialEq, Eq)]
+pub struct GridPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+// Struct references are passed as pointers
+#[julia]
+fn distance(a: &TestPoint, b: &TestPoint) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+#[julia]
+fn translate(point: &mut TestPoint, dx: f64, dy: f64) {
+    point.x += dx;
+    point.y += dy;
+}
+
+// &str parameters arrive as NUL-terminated C strings
+#[julia]
+fn count_vowels(text: &str) -> u32 {
+    text.chars().filter(|c| "aeiouAEIOU".contains(*c)).count() as u32
+}
+
+// Deriving Default adds a zero-argument <Struct>_new_default constructor
+#[julia]
+#[derive(Default)]
+pub struct TickCounter {
+    pub ticks: u64,
+    pub step: i32,
+}
+
+// Mixed-width integer fields report their C width
+#[julia]
+pub struct PackedHeader {
+    pub version: u8,
+    pub flags: i8,
+    pub kind: u16,
+    pub offset: i16,
+    pub length: u32,
+}
+
+// Zero-argument methods: only `self` (or nothing) is passed through the wrapper
+#[julia]
+pub struct Stopwatch {
+    pub laps: u32,
+}
+
+#[julia]
+impl Stopwatch {
+    // Static constructor with no arguments (not named `new`)
+    #[julia]
+    pub fn start() -> Self {
+        Self { laps: 0 }
+    }
+
+    // Static method with no arguments that doesn't return Self
+    #[julia]
+    pub fn max_laps() -> u32 {
+        99
+    }
+
+    // Instance methods whose only argument is self
+    #[julia]
+    pub fn lap(&mut self) {
+        self.laps += 1;
+    }
+
+    #[julia]
+    pub fn lap_count(&self) -> u32 {
+        self.laps
+    }
+}
+
+// Result<*mut T, E> returns CResultPtr_<fn> with a null pointer on error
+#[julia]
+fn try_make_point(n: i32) -> Result<*mut TestPoint, i32> {
+    if n < 0 {
+        return Err(n);
+    }
+    Ok(Box::into_raw(Box::new(TestPoint {
+        x: n as f64,
+        y: 0.0,
+    })))
+}
+
+// thread_safe asserts Send + Sync at compile time
+#[julia(thread_safe)]
+pub struct SharedStats {
+    pub samples: u64,
+    pub mean: f64,
+}
+
+// out_params writes each tuple element through its own pointer
+#[julia(out_params)]
+fn minmax(xs: &[f64]) -> (f64, f64) {
+    xs.iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| {
+            (lo.min(x), hi.max(x))
+        })
+}
+
+// A unit return adds no out pointers
+#[julia(out_params)]
+fn reset_nothing() {}
+
+// iterator = "..." exposes the struct's Iterator impl as <Struct>_next
+#[julia(iterator = "u32")]
+pub struct CountUp {
+    pub current: u32,
+    pub end: u32,
+}
+
+impl Iterator for CountUp {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.current >= self.end {
+            return None;
+        }
+        self.current += 1;
+        Some(self.current)
+    }
+}
+
+// canonical Result/Option types are shared between functions with the same payloads
+juliacall_macros::julia_canonical!(Result<f64, i32>, Option<f64>);
+
+#[julia(canonical)]
+fn canonical_sqrt(x: f64) -> Result<f64, i32> {
+    if x < 0.0 {
+        Err(-1)
+    } else {
+        Ok(x.sqrt())
+    }
+}
+
+#[julia(canonical)]
+fn canonical_recip(x: f64) -> Result<f64, i32> {
+    if x == 0.0 {
+        Err(-2)
+    } else {
+        Ok(1.0 / x)
+    }
+}
+
+#[julia(canonical)]
+fn first_negative(a: f64, b: f64) -> Option<f64> {
+    [a, b].into_iter().find(|x| *x < 0.0)
+}
+
+// Methods returning Option<Self> hand Julia a nullable pointer
+#[julia]
+pub struct Span {
+    pub start: i32,
+    pub end: i32,
+}
+
+#[julia]
+impl Span {
+    // Shrinks by one on each side; None once nothing would be left
+    #[julia]
+    pub fn try_shrink(&self) -> Option<Self> {
+        if self.end - self.start <= 2 {
+            return None;
+        }
+        Some(Self {
+            start: self.start + 1,
+            end: self.end - 1,
+        })
+    }
+
+    // Vec<Self> returns hand back one boxed pointer per element
+    #[julia]
+    pub fn split_at(&self, mid: i32) -> Vec<Span> {
+        vec![
+            Span {
+                start: self.start,
+                end: mid,
+            },
+            Span {
+                start: mid,
+                end: self.end,
+            },
+        ]
+    }
+}
+
+// Static methods returning Result<Self, E> are fallible constructors
+#[julia]
+pub struct Percent {
+    pub value: i32,
+}
+
+#[allow(clippy::should_implement_trait)]
+#[julia]
+impl Percent {
+    #[julia]
+    pub fn from_str(n: i32) -> Result<Self, i32> {
+        if (0..=100).contains(&n) {
+            Ok(Self { value: n })
+        } else {
+            Err(n)
+        }
+    }
+}
+
+// Constants and statics are exported as <name>_value() accessors
+#[julia]
+pub const MAX_USERS: u32 = 1000;
+
+#[julia]
+pub static DEFAULT_TOLERANCE: f64 = 1e-9;
+
+// checked_setters null-check the struct pointer and report success
+#[julia(checked_setters)]
+pub struct Thermostat {
+    pub target: f64,
+    pub schedule: [u8; 2],
+}
+
+// checked_setters also cover the split 128-bit halves and _set_all
+#[julia(checked_setters, split_128)]
+pub struct Odometer {
+    pub distance: u128,
+    pub trips: u32,
+}
+
+// half::f16 fields and parameters cross the C ABI as their u16 bits (half feature)
+#[cfg(feature = "half")]
+#[julia]
+pub struct HalfSample {
+    pub weight: half::f16,
+    pub count: u32,
+}
+
+#[cfg(feature = "half")]
+#[julia(safe_access)]
+pub struct SafeHalf {
+    pub weight: half::f16,
+}
+
+#[cfg(feature = "half")]
+#[julia]
+fn half_to_f32(x: half::f16) -> f32 {
+    x.to_f32()
+}
+
+// opaque returns Box<dyn Trait> as a thin handle Julia only passes back to Rust
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Area for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+#[julia(opaque)]
+fn make_shape(side: f64) -> Box<dyn Area> {
+    Box::new(Square(side))
+}
+
+#[julia]
+fn shape_area(handle: *mut std::ffi::c_void) -> f64 {
+    let shape = unsafe { &*(handle as *const Box<dyn Area>) };
+    shape.area()
+}
+
+/// Version string exported as a static C string
+#[julia]
+fn library_version() -> &'static str {
+    "1.4.2"
+}
+
+#[julia]
+pub struct Point {
+    #[julia(rename = "pos_x")]
+    pub x: f64,
+    pub y: f64,
+}
+
+fn is_non_negative(value: f64) -> bool {
+    value >= 0.0
+}
+
+#[julia]
+pub struct Disk {
+    #[julia(validate = "is_non_negative")]
+    pub radius: f64,
+    pub center_x: f64,
+}
+
+// Scalars around a slice keep their positions: (base, xs_ptr, xs_len)
+#[julia]
+fn weighted(base: i32, xs: &[i32]) -> i32 {
+    xs.iter()
+        .enumerate()
+        .map(|(i, x)| x * (i as i32 + 1))
+        .sum::<i32>()
+        + base
+}
+
+#[julia]
+fn scaled_weighted(base: i32, xs: &[i32], scale: i32) -> Option<i32> {
+    xs.iter()
+        .try_fold(base, |acc, &x| acc.checked_add(x.checked_mul(scale)?))
+}
+
+#[julia]
+fn histogram(xs: &[i32]) -> std::collections::HashMap<i32, u32> {
+    let mut counts = std::collections::HashMap::new();
+    for &x in xs {
+        *counts.entry(x).or_insert(0) += 1;
+    }
+    counts
+}
+
+// Restricted visibility on the source items still yields linkable pub wrappers
+#[julia]
+pub(crate) fn third_of(x: i32) -> Option<i32> {
+    (x % 3 == 0).then_some(x / 3)
+}
+
+#[julia]
+pub struct Gauge {
+    pub level: f64,
+}
+
+#[julia]
+impl Gauge {
+    #[julia]
+    pub(crate) fn new(level: f64) -> Self {
+        Self { level }
+    }
+
+    #[julia]
+    pub(crate) fn headroom(&self, max: f64) -> f64 {
+        max - self.level
+    }
+}
+
+// #[julia] on a module exports its pub items; private helpers stay untouched
+#[julia]
+pub mod geometry {
+    pub struct Rect {
+        pub width: f64,
+        pub height: f64,
+    }
+
+    impl Rect {
+        #[julia]
+        pub fn area(&self) -> f64 {
+            self.width * self.height
+        }
+    }
+
+    pub fn rect_perimeter(width: f64, height: f64) -> f64 {
+        double(width + height)
+    }
+
+    fn double(x: f64) -> f64 {
+        x * 2.0
+    }
+}
+
+// Exported as `rc_scaled_sum` while Rust code keeps calling `scaled_sum`
+#[julia(link_name = "rc_scaled_sum")]
+fn scaled_sum(a: i32, b: i32, scale: i32) -> i32 {
+    (a + b) * scale
+}
+
+extern "C" {
+    fn rc_scaled_sum(a: i32, b: i32, scale: i32) -> i32;
+}
+
+// Borrowed literals are interned (owned = 0); formatted strings are handed over (owned = 1)
+#[julia]
+fn grade_label(score: u32) -> std::borrow::Cow<'static, str> {
+    match score {
+        90.. => std::borrow::Cow::Borrowed("excellent"),
+        _ => std::borrow::Cow::Owned(format!("{score} points")),
+    }
+}
+
+// Generic structs are exported through a concrete alias; `impl Pair<i32>` is rejected
+pub struct Pair<T> {
+    pub first: T,
+    pub second: T,
+}
+
+pub type PairI32 = Pair<i32>;
+
+#[julia]
+impl PairI32 {
+    #[julia]
+    pub fn new(first: i32, second: i32) -> Self {
+        Pair { first, second }
+    }
+
+    #[julia]
+    pub fn sum(&self) -> i32 {
+        self.first + self.second
+    }
+}
+
+// Rust callers get a deprecation warning; the `pair_total` symbol is still exported
+#[julia(deprecated = "use scaled_sum instead")]
+fn pair_total(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+extern "C" {
+    #[link_name = "pair_total"]
+    fn pair_total_symbol(a: i32, b: i32) -> i32;
+}
+
+// NonZero parameters and returns cross the boundary as the plain integer
+#[julia]
+fn halve_nonzero(n: std::num::NonZeroU32) -> u32 {
+    n.get() / 2
+}
+
+#[julia]
+fn successor(n: u32) -> std::num::NonZeroU32 {
+    std::num::NonZeroU32::new(n.saturating_add(1)).unwrap_or(std::num::NonZeroU32::MAX)
+}
+
+#[julia]
+fn divide_by(total: i32, divisor: std::num::NonZero<i32>) -> i32 {
+    total / divisor.get()
+}
+
+// C callbacks pass through unchanged; Julia supplies them with @cfunction
+#[julia]
+fn apply(cb: extern "C" fn(i32) -> i32, x: i32) -> i32 {
+    cb(x)
+}
+
+extern "C" fn triple(x: i32) -> i32 {
+    x * 3
+}
+
+// Stored callbacks get the usual accessors
+#[julia]
+pub struct Hook {
+    pub callback: extern "C" fn(i32) -> i32,
+    pub calls: u32,
+}
+
+// Arrays of callbacks have no default element, so the indexed getter returns Option<fn>
+#[julia]
+pub struct Pipeline {
+    pub stages: [extern "C" fn(i32) -> i32; 2],
+}
+
+extern "C" fn negate(x: i32) -> i32 {
+    -x
+}
+
 fn main() {
     // Verify the functions are callable
     let result = simple_add(1, 2);
@@ -234,6 +1202,773 @@ fn main() {
     Builder_free(builder_ptr);
     Builder_free(builder2_ptr);
 
+    // Test enums with explicit discriminants
+    assert_eq!(Color_to_i32(Color::Red), 1);
+    assert_eq!(Color_to_i32(Color::Green), 2);
+    assert_eq!(Color_to_i32(Color::Blue), 7);
+    assert!(Color_is_valid_i32(7));
+    assert!(!Color_is_valid_i32(3));
+    assert_eq!(std::mem::size_of::<Direction>(), 1);
+    assert_eq!(Direction_to_u8(Direction::West), 3);
+    assert!(Direction_is_valid_u8(0));
+    assert!(!Direction_is_valid_u8(4));
+
+    // Test null-checked getters
+    let mut safe_point = SafePoint { x: 3.0, y: 4.0 };
+    let safe_ptr = &mut safe_point as *mut SafePoint;
+    let got_x = SafePoint_get_x(safe_ptr);
+    assert_eq!(got_x.is_some, 1);
+    assert!((got_x.value - 3.0).abs() < 1e-10);
+    let got_null = SafePoint_get_y(std::ptr::null());
+    assert_eq!(got_null.is_some, 0);
+    let safe_hook = SafeHook { callback: triple };
+    let callback = SafeHook_get_callback(&safe_hook).expect("non-null hook");
+    assert_eq!(callback(4), 12);
+    assert!(SafeHook_get_callback(std::ptr::null()).is_none());
+
+    // Test errno-style error reporting
+    assert_eq!(checked_sqrt(-1.0), 0.0);
+    assert_eq!(basic_last_error(), 22);
+    let message = unsafe { std::ffi::CStr::from_ptr(basic_last_error_message()) };
+    assert_eq!(message.to_str().unwrap(), "input must be non-negative");
+    assert!((checked_sqrt(9.0) - 3.0).abs() < 1e-10);
+    assert_eq!(basic_last_error(), 0);
+    assert!(basic_last_error_message().is_null());
+
+    // Test type alias re-exports
+    let mut vec2 = Vec2 { x: 1.5, y: -2.0 };
+    let vec2_ptr = &mut vec2 as *mut Vec2;
+    assert!((Vec2_get_x(vec2_ptr) - 1.5).abs() < 1e-10);
+    Vec2_set_y(vec2_ptr, 4.0);
+    assert!((TestPoint_get_y(vec2_ptr) - 4.0).abs() < 1e-10);
+
+    // Test struct swap
+    let mut first = TestPoint { x: 1.0, y: 2.0 };
+    let mut second = TestPoint { x: 10.0, y: 20.0 };
+    TestPoint_swap(&mut first, &mut second);
+    assert!((first.x - 10.0).abs() < 1e-10 && (first.y - 20.0).abs() < 1e-10);
+    assert!((second.x - 1.0).abs() < 1e-10 && (second.y - 2.0).abs() < 1e-10);
+    TestPoint_swap(&mut first, std::ptr::null_mut());
+    assert!((first.x - 10.0).abs() < 1e-10);
+
+    // Test Debug formatting of structs
+    let debug_point = TestPoint { x: 1.0, y: 2.0 };
+    let debug_ptr = TestPoint_debug_string(&debug_point);
+    let debug_text = unsafe { std::ffi::CStr::from_ptr(debug_ptr) };
+    assert!(debug_text.to_str().unwrap().contains("TestPoint"));
+    TestPoint_debug_string_free(debug_ptr);
+    assert!(TestPoint_debug_string(std::ptr::null()).is_null());
+
+    // Test &'static str method returns
+    let shape_ptr = Shape_new(4);
+    let name_ptr = Shape_kind_name(shape_ptr);
+    let name = unsafe { std::ffi::CStr::from_ptr(name_ptr) };
+    assert_eq!(name.to_str().unwrap(), "square");
+    assert_eq!(Shape_kind_name(shape_ptr), name_ptr);
+    let family = unsafe { std::ffi::CStr::from_ptr(Shape_family()) };
+    assert_eq!(family.to_str().unwrap(), "shape");
+    Shape_free(shape_ptr);
+
+    // Test Result<(), E> functions
+    let committed = commit(1);
+    assert_eq!(committed.is_ok, 1);
+    let rejected = commit(-3);
+    assert_eq!(rejected.is_ok, 0);
+    assert_eq!(rejected.err_value, -3);
+    assert_eq!(
+        std::mem::size_of::<CResultUnit_commit>(),
+        2 * std::mem::size_of::<i32>()
+    );
+
+    // Test repr(C) verification
+    let labeled = Labeled {
+        id: 7,
+        payload: Box::new(49),
+    };
+    assert_eq!(*labeled.payload, 49);
+    assert_eq!(Labeled_get_id(&labeled), 7);
+    let verified = Verified { id: 1, weight: 0.5 };
+    assert_eq!(Verified_get_id(&verified), 1);
+
+    // Test Result<T, Box<E>> functions
+    let loaded = load_setting(4);
+    assert_eq!(loaded.is_ok, 1);
+    assert!((loaded.ok_value - 2.0).abs() < 1e-10);
+    assert!(loaded.err_value.is_null());
+    CResult_load_setting_free(loaded);
+    let failed = load_setting(0);
+    assert_eq!(failed.is_ok, 0);
+    assert_eq!(LoadError_get_code(failed.err_value), 404);
+    assert_eq!(LoadError_get_line(failed.err_value), 0);
+    CResult_load_setting_free(failed);
+
+    // Test iteration over struct arrays
+    let mut points = [
+        TestPoint { x: 1.0, y: 0.0 },
+        TestPoint { x: 2.0, y: 0.0 },
+        TestPoint { x: 3.0, y: 0.0 },
+    ];
+    let iter = TestPoint_iter_new(points.as_mut_ptr(), points.len());
+    let mut visited = Vec::new();
+    loop {
+        let item = TestPoint_iter_next(iter);
+        if item.is_null() {
+            break;
+        }
+        visited.push(TestPoint_get_x(item));
+    }
+    assert_eq!(visited, vec![1.0, 2.0, 3.0]);
+    assert!(TestPoint_iter_next(iter).is_null());
+    TestPoint_iter_free(iter);
+
+    // Test slice parameters
+    let samples = [1.0, 2.0, 3.0, 6.0];
+    assert!((mean(samples.as_ptr(), samples.len()) - 3.0).abs() < 1e-10);
+    assert_eq!(mean(std::ptr::null(), 0), 0.0);
+    let checked = checked_mean(samples.as_ptr(), samples.len());
+    assert_eq!(checked.is_some, 1);
+    assert!((checked.value - 3.0).abs() < 1e-10);
+    assert_eq!(checked_mean(std::ptr::null(), 0).is_some, 0);
+
+    // Test multiple slices with a checked shared length
+    let x = [1.0, 2.0, 3.0];
+    let mut y = [10.0, 20.0, 30.0];
+    if std::env::var_os("JULIACALL_AXPY_MISMATCH").is_some() {
+        axpy(2.0, x.as_ptr(), x.len(), y.as_mut_ptr(), 2);
+        return;
+    }
+    axpy(2.0, x.as_ptr(), x.len(), y.as_mut_ptr(), y.len());
+    assert_eq!(y, [12.0, 24.0, 36.0]);
+    axpy(2.0, std::ptr::null(), 0, std::ptr::null_mut(), 0);
+    #[cfg(debug_assertions)]
+    {
+        // A length mismatch trips the debug assertion (aborting at the extern "C" boundary)
+        let status = std::process::Command::new(std::env::current_exe().unwrap())
+            .env("JULIACALL_AXPY_MISMATCH", "1")
+            .stderr(std::process::Stdio::null())
+            .status()
+            .unwrap();
+        assert!(!status.success());
+    }
+
+    // Test thread-local scratch buffers
+    assert_eq!(scratch_sum_squares(4), 14.0);
+    assert_eq!(scratch_sum_squares(4), 14.0);
+    let address = scratch_address();
+    for _ in 0..100 {
+        assert_eq!(scratch_address(), address);
+    }
+    let other_thread = std::thread::spawn(|| scratch_address()).join().unwrap();
+    assert_ne!(other_thread, address);
+
+    // Test out-param returns
+    let mut transform = std::mem::MaybeUninit::<Transform>::uninit();
+    make_transform(2.0, 3.0, 4.0, transform.as_mut_ptr());
+    let transform = unsafe { transform.assume_init() };
+    assert_eq!(transform.scale_x, 2.0);
+    assert_eq!(transform.scale_y, 2.0);
+    assert_eq!(transform.shear_x, 0.0);
+    assert_eq!(transform.shear_y, 0.0);
+    assert_eq!(transform.offset_x, 3.0);
+    assert_eq!(transform.offset_y, 4.0);
+    make_transform(1.0, 0.0, 0.0, std::ptr::null_mut());
+
+    // Test checked getters for nullable pointer fields
+    let mut root = TreeNode {
+        value: 1,
+        parent: std::ptr::null_mut(),
+    };
+    let child = TreeNode {
+        value: 2,
+        parent: &mut root,
+    };
+    assert_eq!(TreeNode_get_parent_checked(&root).is_some, 0);
+    let parent = TreeNode_get_parent_checked(&child);
+    assert_eq!(parent.is_some, 1);
+    assert_eq!(TreeNode_get_value(parent.value), 1);
+    assert_eq!(TreeNode_get_parent_checked(std::ptr::null()).is_some, 0);
+
+    // Test field diff masks
+    let before = Settings {
+        width: 640,
+        height: 480,
+        scale: 1.0,
+        visible: true,
+    };
+    let after = Settings {
+        width: 640,
+        height: 720,
+        scale: 1.0,
+        visible: false,
+    };
+    assert_eq!(Settings_diff_mask(&before, &before), 0);
+    assert_eq!(Settings_diff_mask(&before, &after), 0b1010);
+    assert_eq!(Settings_diff_mask(&before, std::ptr::null()), 0b1111);
+
+    // Test nested struct fields accessed by pointer
+    let mut circle = Circle {
+        center: TestPoint { x: 1.0, y: 2.0 },
+        radius: 3.0,
+    };
+    let center = Circle_get_center(&circle);
+    assert_eq!(TestPoint_get_x(center), 1.0);
+    assert_eq!(TestPoint_get_y(center), 2.0);
+    let moved = TestPoint { x: -4.0, y: 5.0 };
+    Circle_set_center(&mut circle, &moved);
+    assert_eq!(TestPoint_get_x(Circle_get_center(&circle)), -4.0);
+    assert_eq!(Circle_get_radius(&circle), 3.0);
+    assert!(Circle_get_center(std::ptr::null()).is_null());
+    let counter = HitCounter {
+        hits: std::sync::atomic::AtomicU32::new(2),
+        limit: 5,
+    };
+    assert_eq!(HitCounter_get_limit(&counter), 5);
+    let timing = Timing {
+        elapsed: std::time::Duration::from_millis(1500),
+        laps: 3,
+    };
+    assert_eq!(Timing_get_laps(&timing), 3);
+    assert_eq!(timing.elapsed.as_millis(), 1500);
+
+    // Test Result<Vec<T>, String> with a combined free
+    let input = b"1.5, 2, -3";
+    let parsed = parse_numbers(input.as_ptr(), input.len());
+    assert_eq!(parsed.is_ok, 1);
+    assert!(parsed.err_value.is_null());
+    let values = unsafe { std::slice::from_raw_parts(parsed.ok_value.ptr, parsed.ok_value.len) };
+    assert_eq!(values, &[1.5, 2.0, -3.0]);
+    CResult_parse_numbers_free(parsed);
+    let input = b"1, x";
+    let failed = parse_numbers(input.as_ptr(), input.len());
+    assert_eq!(failed.is_ok, 0);
+    assert!(failed.ok_value.ptr.is_null());
+    let message = unsafe { std::ffi::CStr::from_ptr(failed.err_value) };
+    assert_eq!(message.to_str().unwrap(), "invalid number: \"x\"");
+    CResult_parse_numbers_free(failed);
+
+    // Test double-free detection with track_frees
+    let ticket = Ticket_new(7);
+    assert_eq!(Ticket_get_id(ticket), 7);
+    assert_eq!(Ticket_invalid_free_count(), 0);
+    Ticket_free(ticket);
+    assert_eq!(Ticket_invalid_free_count(), 0);
+    Ticket_free(ticket);
+    assert_eq!(Ticket_invalid_free_count(), 1);
+    let mut stack_ticket = Ticket { id: 1 };
+    Ticket_free(&mut stack_ticket);
+    assert_eq!(Ticket_invalid_free_count(), 2);
+
+    // Test Box<T> returns handed over as *mut T
+    let point = make_point(1.5, -2.5);
+    assert_eq!(TestPoint_get_x(point), 1.5);
+    assert_eq!(TestPoint_get_y(point), -2.5);
+    TestPoint_free(point);
+    let ticket = issue_ticket(9);
+    assert_eq!(Ticket_get_id(ticket), 9);
+    Ticket_free(ticket);
+    assert_eq!(Ticket_invalid_free_count(), 2);
+
+    // Test Result<T, String> with a taken C string error
+    let input = b" 42 ";
+    assert_eq!(parse_count(input.as_ptr(), input.len()), 42);
+    assert!(parse_count_last_error().is_null());
+    let input = b"forty";
+    assert_eq!(parse_count(input.as_ptr(), input.len()), 0);
+    let message = parse_count_last_error();
+    assert!(!message.is_null());
+    let text = unsafe { std::ffi::CStr::from_ptr(message) };
+    assert_eq!(
+        text.to_str().unwrap(),
+        "cannot parse \"forty\" as a count: invalid digit found in string"
+    );
+    parse_count_last_error_free(message);
+    assert!(parse_count_last_error().is_null());
+
+    // Test indexed accessors for array fields
+    let mut v3 = Vec3 { data: [0.0; 3] };
+    for i in 0..3 {
+        Vec3_set_data_at(&mut v3, i, i as f64 + 0.5);
+    }
+    for i in 0..3 {
+        assert_eq!(Vec3_get_data_at(&v3, i), i as f64 + 0.5);
+    }
+    Vec3_set_data_at(&mut v3, 3, 99.0);
+    assert_eq!(v3.data, [0.5, 1.5, 2.5]);
+    assert_eq!(Vec3_get_data_at(&v3, 3), 0.0);
+
+    // Test structs without a _free function
+    let stats = global_stats();
+    GlobalStats_set_calls(stats, 3);
+    assert_eq!(GlobalStats_get_calls(stats), 3);
+
+    // Test positional accessors for tuple structs
+    let mut temp = Celsius(21.5);
+    assert_eq!(Celsius_get_0(&temp), 21.5);
+    Celsius_set_0(&mut temp, -4.0);
+    assert_eq!(temp.0, -4.0);
+    Marker_free(Box::into_raw(Box::new(Marker)));
+
+    // Test Option payloads of enums that accept zero
+    let west = direction_from_code(3);
+    assert_eq!(west.is_some, 1);
+    assert_eq!(Direction_to_u8(west.value), 3);
+    assert_eq!(direction_from_code(9).is_some, 0);
+
+    // Test fixed-size array returns
+    assert_eq!(first_odds(4).data, [5, 7, 9]);
+    assert_eq!(std::mem::size_of::<CArr_first_odds>(), 3 * 4);
+
+    // Test #[julia(all)] impl blocks
+    let acc = Accumulator_new(1.0);
+    Accumulator_push(acc, 4.0);
+    Accumulator_push(acc, 7.0);
+    assert_eq!(Accumulator_mean(acc), 4.0);
+    assert_eq!(Accumulator_get_count(acc), 3);
+    assert_eq!(unsafe { (*acc).summary() }, "3 values, sum 12");
+    assert_eq!(Accumulator_summary(), "no wrapper");
+    Accumulator_free(acc);
+
+    // Test split 128-bit field accessors
+    let mut ledger = Ledger {
+        total: 0,
+        delta: 0,
+        entries: 0,
+    };
+    let big: u128 = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+    Ledger_set_total_lo(&mut ledger, big as u64);
+    Ledger_set_total_hi(&mut ledger, (big >> 64) as u64);
+    assert_eq!(ledger.total, big);
+    assert_eq!(Ledger_get_total_lo(&ledger), 0xfedc_ba98_7654_3210);
+    assert_eq!(Ledger_get_total_hi(&ledger), 0x0123_4567_89ab_cdef);
+    Ledger_set_delta_lo(&mut ledger, u64::MAX - 4);
+    Ledger_set_delta_hi(&mut ledger, u64::MAX);
+    assert_eq!(ledger.delta, -5);
+    assert_eq!(Ledger_get_delta_hi(&ledger), u64::MAX);
+    Ledger_set_entries(&mut ledger, 2);
+    assert_eq!(Ledger_get_entries(&ledger), 2);
+    let safe_ledger = SafeLedger {
+        total: (3u128 << 64) | 4,
+    };
+    let hi = SafeLedger_get_total_hi(&safe_ledger);
+    assert_eq!((hi.is_some, hi.value), (1, 3));
+    assert_eq!(SafeLedger_get_total_lo(&safe_ledger).value, 4);
+    assert_eq!(SafeLedger_get_total_lo(std::ptr::null()).is_some, 0);
+    assert_eq!(SafeLedger_get_total_hi(std::ptr::null()).is_some, 0);
+
+    // Test Option<*mut T> returns
+    let node = new_positive_node(5);
+    assert!(!node.is_null());
+    assert_eq!(TreeNode_get_value(node), 5);
+    TreeNode_free(node);
+    assert!(new_positive_node(-1).is_null());
+
+    // Test feature-gated exports
+    #[cfg(feature = "python")]
+    {
+        let mut sample = GatedSample { value: 1.0 };
+        GatedSample_set_value(&mut sample, 3.0);
+        assert_eq!(GatedSample_get_value(&sample), 3.0);
+        assert_eq!(gated_double(2.0).value, 4.0);
+    }
+
+    // Test methods returning Vec<T>
+    let triangle = Triangle {
+        a: 3.0,
+        b: 4.0,
+        c: 5.0,
+    };
+    let sides = Triangle_sides(&triangle);
+    assert_eq!(sides.len, 3);
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(sides.ptr, sides.len) },
+        [3.0, 4.0, 5.0]
+    );
+    CVec_Triangle_sides_free(sides);
+    let rounded = Triangle_rounded_sides(&triangle);
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(rounded.ptr, rounded.len) },
+        [3, 4, 5]
+    );
+    CVec_Triangle_rounded_sides_free(rounded);
+
+    // Test bulk field setters
+    let mut rect = Rectangle {
+        width: 1.0,
+        height: 1.0,
+        tag: *b"unit",
+    };
+    Rectangle_set_all(&mut rect, 4.0, 2.5);
+    assert_eq!((rect.width, rect.height), (4.0, 2.5));
+    assert_eq!(&rect.tag, b"unit");
+    Rectangle_set_all(std::ptr::null_mut(), 0.0, 0.0);
+    let mut temp = Celsius(0.0);
+    Celsius_set_all(&mut temp, 37.0);
+    assert_eq!(temp.0, 37.0);
+
+    // Test ABI overrides
+    let halve: extern "C-unwind" fn(i32) -> COption_halve_even = halve_even;
+    assert_eq!(halve(8).value, 4);
+    assert_eq!(halve(7).is_some, 0);
+
+    // Test Option<T> field accessors
+    let mut node = Node { id: None };
+    assert_eq!(Node_get_id(&node).is_some, 0);
+    Node_set_id(&mut node, 1, 17);
+    assert_eq!(node.id, Some(17));
+    let id = Node_get_id(&node);
+    assert_eq!((id.is_some, id.value), (1, 17));
+    Node_set_id(&mut node, 0, 99);
+    assert_eq!(node.id, None);
+    assert_eq!(Node_get_id(std::ptr::null()).is_some, 0);
+
+    // Test struct hashing
+    let a = GridPoint { x: 3, y: -4 };
+    let b = GridPoint { x: 3, y: -4 };
+    let c = GridPoint { x: -4, y: 3 };
+    assert_eq!(GridPoint_hash(&a), GridPoint_hash(&b));
+    assert_ne!(GridPoint_hash(&a), GridPoint_hash(&c));
+    assert_eq!(GridPoint_hash(std::ptr::null()), 0);
+
+    // Test struct reference parameters
+    let mut origin = TestPoint { x: 0.0, y: 0.0 };
+    let corner = TestPoint { x: 3.0, y: 4.0 };
+    assert_eq!(distance(&origin, &corner), 5.0);
+    translate(&mut origin, 3.0, 0.0);
+    assert_eq!(distance(&origin, &corner), 4.0);
+
+    // Test &str parameter lowering
+    let text = std::ffi::CString::new("Julia and Rust").unwrap();
+    assert_eq!(count_vowels(text.as_ptr()), 5);
+
+    // Test _new_default for Default structs
+    let tick_counter = TickCounter_new_default();
+    assert!(!tick_counter.is_null());
+    assert_eq!(TickCounter_get_ticks(tick_counter), 0);
+    assert_eq!(TickCounter_get_step(tick_counter), 0);
+    TickCounter_free(tick_counter);
+
+    // Test _field_width_<field> for narrow integers
+    assert_eq!(PackedHeader_field_width_version(), 1);
+    assert_eq!(PackedHeader_field_width_flags(), 1);
+    assert_eq!(PackedHeader_field_width_kind(), 2);
+    assert_eq!(PackedHeader_field_width_offset(), 2);
+    assert_eq!(PackedHeader_field_width_length(), 4);
+    assert_eq!(Celsius_field_width_0(), 8);
+
+    // Test _field_offset_<field> against the repr(C) layout
+    assert_eq!(PackedHeader_field_offset_version(), 0);
+    assert_eq!(PackedHeader_field_offset_kind(), 2);
+    assert_eq!(PackedHeader_field_offset_length(), 6 + 2);
+    assert_eq!(Point_field_offset_pos_x(), 0);
+    assert_eq!(Point_field_offset_y(), 8);
+    assert_eq!(Celsius_field_offset_0(), 0);
+
+    // Test _box taking a struct by value
+    let boxed_point = TestPoint_box(TestPoint { x: 1.5, y: -2.0 });
+    assert!(!boxed_point.is_null());
+    assert_eq!(TestPoint_get_x(boxed_point), 1.5);
+    assert_eq!(TestPoint_get_y(boxed_point), -2.0);
+    TestPoint_free(boxed_point);
+
+    // Test zero-argument static and instance methods
+    let stopwatch = Stopwatch_start();
+    assert!(!stopwatch.is_null());
+    assert_eq!(Stopwatch_lap_count(stopwatch), 0);
+    Stopwatch_lap(stopwatch);
+    Stopwatch_lap(stopwatch);
+    assert_eq!(Stopwatch_lap_count(stopwatch), 2);
+    assert_eq!(Stopwatch_max_laps(), 99);
+    Stopwatch_free(stopwatch);
+
+    // Test Result<*mut T, E> returns
+    let made = try_make_point(3);
+    assert_eq!(made.is_ok, 1);
+    assert!(!made.ptr.is_null());
+    assert_eq!(made.err, 0);
+    assert_eq!(TestPoint_get_x(made.ptr), 3.0);
+    TestPoint_free(made.ptr);
+    let failed = try_make_point(-4);
+    assert_eq!(failed.is_ok, 0);
+    assert!(failed.ptr.is_null());
+    assert_eq!(failed.err, -4);
+
+    // Test thread_safe structs still get their FFI functions
+    let shared_stats = SharedStats_box(SharedStats {
+        samples: 4,
+        mean: 2.5,
+    });
+    assert_eq!(SharedStats_get_samples(shared_stats), 4);
+    SharedStats_free(shared_stats);
+
+    // Test out_params for tuple returns
+    let samples = [3.0, -1.5, 8.25, 0.0];
+    let (mut lo, mut hi) = (0.0, 0.0);
+    minmax(samples.as_ptr(), samples.len(), &mut lo, &mut hi);
+    assert_eq!((lo, hi), (-1.5, 8.25));
+    minmax(
+        samples.as_ptr(),
+        samples.len(),
+        std::ptr::null_mut(),
+        &mut hi,
+    );
+    assert_eq!(hi, 8.25);
+    reset_nothing();
+
+    // Test <Struct>_next for iterator structs
+    let count_up = CountUp_box(CountUp { current: 0, end: 3 });
+    let mut stepped = Vec::new();
+    loop {
+        let next = CountUp_next(count_up);
+        if next.is_some == 0 {
+            break;
+        }
+        stepped.push(next.value);
+    }
+    assert_eq!(stepped, vec![1, 2, 3]);
+    assert_eq!(CountUp_next(count_up).is_some, 0);
+    assert_eq!(CountUp_next(std::ptr::null_mut()).is_some, 0);
+    CountUp_free(count_up);
+
+    // Test canonical CResult/COption types shared across functions
+    let results: [CResult_f64_i32; 2] = [canonical_sqrt(9.0), canonical_recip(0.0)];
+    assert_eq!((results[0].is_ok, results[0].ok_value), (1, 3.0));
+    assert_eq!((results[1].is_ok, results[1].err_value), (0, -2));
+    let negative: COption_f64 = first_negative(2.0, -0.5);
+    assert_eq!((negative.is_some, negative.value), (1, -0.5));
+
+    // Test methods returning Option<Self>
+    let span = Span_box(Span { start: 0, end: 4 });
+    let shrunk = Span_try_shrink(span);
+    assert!(!shrunk.is_null());
+    assert_eq!((Span_get_start(shrunk), Span_get_end(shrunk)), (1, 3));
+    assert!(Span_try_shrink(shrunk).is_null());
+    Span_free(shrunk);
+    Span_free(span);
+
+    // Test methods returning Vec<Self>
+    let span = Span_box(Span { start: 0, end: 10 });
+    let halves = Span_split_at(span, 4);
+    assert_eq!(halves.len, 2);
+    let parts = unsafe { std::slice::from_raw_parts(halves.ptr, halves.len) };
+    let ranges: Vec<(i32, i32)> = parts
+        .iter()
+        .map(|&part| {
+            let part = part as *mut Span;
+            let range = (Span_get_start(part), Span_get_end(part));
+            Span_free(part);
+            range
+        })
+        .collect();
+    assert_eq!(ranges, [(0, 4), (4, 10)]);
+    CVec_Span_split_at_free(halves);
+    Span_free(span);
+
+    // Test fallible constructors returning Result<Self, E>
+    let percent = Percent_from_str(42);
+    assert!(!percent.is_null());
+    assert_eq!(Percent_get_value(percent), 42);
+    Percent_free(percent);
+    assert!(Percent_from_str(140).is_null());
+
+    // Test const/static value accessors
+    assert_eq!(MAX_USERS_value(), MAX_USERS);
+    assert_eq!(MAX_USERS_value(), 1000);
+    assert_eq!(DEFAULT_TOLERANCE_value(), 1e-9);
+
+    // Test checked setters returning bool
+    let thermostat = Thermostat_box(Thermostat {
+        target: 20.0,
+        schedule: [0, 0],
+    });
+    assert!(Thermostat_set_target(thermostat, 21.5));
+    assert_eq!(Thermostat_get_target(thermostat), 21.5);
+    assert!(!Thermostat_set_target(std::ptr::null_mut(), 18.0));
+    assert!(Thermostat_set_schedule_at(thermostat, 1, 7));
+    assert!(!Thermostat_set_schedule_at(thermostat, 2, 7));
+    Thermostat_free(thermostat);
+
+    let odometer = Odometer_box(Odometer {
+        distance: 0,
+        trips: 0,
+    });
+    assert!(Odometer_set_distance_hi(odometer, 1));
+    assert!(Odometer_set_distance_lo(odometer, 2));
+    assert_eq!(unsafe { (*odometer).distance }, (1u128 << 64) | 2);
+    assert!(!Odometer_set_distance_lo(std::ptr::null_mut(), 3));
+    assert!(!Odometer_set_distance_hi(std::ptr::null_mut(), 3));
+    // With i128_abi the u128 field is also a _set_all parameter
+    #[cfg(not(feature = "i128_abi"))]
+    let (written, rejected) = (
+        Odometer_set_all(odometer, 4),
+        Odometer_set_all(std::ptr::null_mut(), 5),
+    );
+    #[cfg(feature = "i128_abi")]
+    let (written, rejected) = (
+        Odometer_set_all(odometer, 7, 4),
+        Odometer_set_all(std::ptr::null_mut(), 7, 5),
+    );
+    assert!(written && !rejected);
+    assert_eq!(Odometer_get_trips(odometer), 4);
+    Odometer_free(odometer);
+
+    // Test half::f16 lowering to u16 bits
+    #[cfg(feature = "half")]
+    {
+        let one_and_half = half::f16::from_f32(1.5).to_bits();
+        let sample = HalfSample_box(HalfSample {
+            weight: half::f16::ZERO,
+            count: 1,
+        });
+        HalfSample_set_weight(sample, one_and_half);
+        assert_eq!(HalfSample_get_weight(sample), one_and_half);
+        assert_eq!(HalfSample_field_width_weight(), 2);
+        HalfSample_set_all(sample, half::f16::ONE.to_bits(), 2);
+        assert_eq!(HalfSample_get_weight(sample), half::f16::ONE.to_bits());
+        HalfSample_free(sample);
+        assert_eq!(half_to_f32(one_and_half), 1.5);
+
+        let safe = SafeHalf {
+            weight: half::f16::ONE,
+        };
+        let weight = SafeHalf_get_weight(&safe);
+        assert_eq!(
+            (weight.is_some, weight.value),
+            (1, half::f16::ONE.to_bits())
+        );
+        assert_eq!(SafeHalf_get_weight(std::ptr::null()).is_some, 0);
+    }
+
+    // Test opaque Box<dyn Trait> handles
+    let shape = make_shape(3.0);
+    assert!(!shape.is_null());
+    assert_eq!(shape_area(shape), 9.0);
+    make_shape_free(shape);
+    make_shape_free(std::ptr::null_mut());
+
+    let version = unsafe { std::ffi::CStr::from_ptr(library_version()) };
+    assert_eq!(version.to_str().unwrap(), "1.4.2");
+    assert_eq!(library_version(), library_version());
+
+    let mut point = Point { x: 1.5, y: 2.0 };
+    Point_set_pos_x(&mut point, 4.0);
+    assert_eq!(Point_get_pos_x(&point), 4.0);
+    assert_eq!(point.x, 4.0);
+    assert_eq!(Point_get_y(&point), 2.0);
+
+    let mut disk = Disk {
+        radius: 1.0,
+        center_x: 0.0,
+    };
+    assert!(Disk_set_radius(&mut disk, 2.5));
+    assert!(!Disk_set_radius(&mut disk, -1.0));
+    assert!(!Disk_set_radius(std::ptr::null_mut(), 3.0));
+    assert_eq!(Disk_get_radius(&disk), 2.5);
+    assert!(!Disk_set_all(&mut disk, -4.0, 9.0));
+    assert_eq!((disk.radius, disk.center_x), (2.5, 0.0));
+    assert!(Disk_set_all(&mut disk, 4.0, 9.0));
+    assert_eq!((disk.radius, disk.center_x), (4.0, 9.0));
+
+    let xs = [3, 4, 5];
+    assert_eq!(weighted(100, xs.as_ptr(), xs.len()), 100 + 3 + 8 + 15);
+    assert_eq!(weighted(7, std::ptr::null(), 0), 7);
+    let scaled = scaled_weighted(1, xs.as_ptr(), xs.len(), 10);
+    assert_eq!((scaled.is_some, scaled.value), (1, 121));
+    assert_eq!(
+        scaled_weighted(0, xs.as_ptr(), xs.len(), i32::MAX).is_some,
+        0
+    );
+
+    let samples = [3, 1, 3, 2, 3, 1];
+    let map = histogram(samples.as_ptr(), samples.len());
+    assert_eq!((map.keys.len, map.values.len), (3, 3));
+    let keys = unsafe { std::slice::from_raw_parts(map.keys.ptr, map.keys.len) };
+    let values = unsafe { std::slice::from_raw_parts(map.values.ptr, map.values.len) };
+    let mut pairs: Vec<(i32, u32)> = keys.iter().copied().zip(values.iter().copied()).collect();
+    pairs.sort();
+    assert_eq!(pairs, [(1, 2), (2, 1), (3, 3)]);
+    CMap_histogram_free(map);
+    let empty = histogram(std::ptr::null(), 0);
+    assert_eq!((empty.keys.len, empty.values.len), (0, 0));
+    CMap_histogram_free(empty);
+
+    let third = third_of(12);
+    assert_eq!((third.is_some, third.value), (1, 4));
+    assert_eq!(third_of(7).is_some, 0);
+    let gauge = Gauge_new(0.25);
+    assert_eq!(Gauge_headroom(gauge, 1.0), 0.75);
+    Gauge_free(gauge);
+
+    let rect = geometry::Rect {
+        width: 3.0,
+        height: 4.0,
+    };
+    assert_eq!(geometry::Rect_area(&rect), 12.0);
+    assert_eq!(geometry::Rect_get_width(&rect), 3.0);
+    assert_eq!(geometry::rect_perimeter(3.0, 4.0), 14.0);
+
+    assert_eq!(scaled_sum(2, 3, 4), 20);
+    assert_eq!(unsafe { rc_scaled_sum(2, 3, 4) }, 20);
+
+    let borrowed = grade_label(95);
+    assert_eq!(borrowed.owned, 0);
+    let text = unsafe { std::ffi::CStr::from_ptr(borrowed.ptr) };
+    assert_eq!(text.to_str().unwrap(), "excellent");
+    assert_eq!(grade_label(99).ptr, borrowed.ptr);
+    let owned = grade_label(42);
+    assert_eq!(owned.owned, 1);
+    let text = unsafe { std::ffi::CStr::from_ptr(owned.ptr) };
+    assert_eq!(text.to_str().unwrap(), "42 points");
+    CCowStr_grade_label_free(owned);
+    CCowStr_grade_label_free(borrowed);
+
+    let pair = PairI32_new(2, 5);
+    assert_eq!(PairI32_sum(pair), 7);
+    drop(unsafe { Box::from_raw(pair) });
+
+    #[allow(deprecated)]
+    let total = pair_total(2, 3);
+    assert_eq!(total, 5);
+    assert_eq!(unsafe { pair_total_symbol(2, 3) }, 5);
+
+    assert_eq!(halve_nonzero(9), 4);
+    assert_eq!(successor(41), 42);
+    assert_eq!(divide_by(12, -4), -3);
+
+    let mut points = [
+        Point_box(Point { x: 1.0, y: 2.0 }),
+        std::ptr::null_mut(),
+        Point_box(Point { x: 3.0, y: 4.0 }),
+        Point_box(Point { x: 5.0, y: 6.0 }),
+    ];
+    Point_free_array(points.as_mut_ptr(), points.len());
+    Point_free_array(std::ptr::null_mut(), 3);
+    let mut tickets = [Ticket_new(1), Ticket_new(2), Ticket_new(3)];
+    let invalid = Ticket_invalid_free_count();
+    Ticket_free_array(tickets.as_mut_ptr(), tickets.len());
+    assert_eq!(Ticket_invalid_free_count(), invalid);
+    Ticket_free_array(tickets.as_mut_ptr(), tickets.len());
+    assert_eq!(Ticket_invalid_free_count(), invalid + 3);
+
+    assert_eq!(apply(triple, 7), 21);
+
+    let hook = Hook_box(Hook {
+        callback: triple,
+        calls: 0,
+    });
+    assert_eq!(apply(Hook_get_callback(hook), 2), 6);
+    Hook_set_callback(hook, negate);
+    assert_eq!(apply(Hook_get_callback(hook), 2), -2);
+    Hook_free(hook);
+
+    let mut pipeline = Pipeline {
+        stages: [triple, triple],
+    };
+    Pipeline_set_stages_at(&mut pipeline, 1, negate);
+    let stage = Pipeline_get_stages_at(&pipeline, 1).expect("index in bounds");
+    assert_eq!(stage(2), -2);
+    assert!(Pipeline_get_stages_at(&pipeline, 2).is_none());
+    assert!(Pipeline_get_stages_at(std::ptr::null(), 0).is_none());
+
     println!("All tests passed!");
 }
 
@@ -3,4 +3,28 @@ fn ui_tests() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/non_ffi_result.rs");
     t.compile_fail("tests/ui/non_ffi_option.rs");
+    t.compile_fail("tests/ui/alias_non_julia.rs");
+    t.compile_fail("tests/ui/non_repr_c_field.rs");
+    t.compile_fail("tests/ui/method_returns_reference.rs");
+    t.compile_fail("tests/ui/destructured_param.rs");
+    t.compile_fail("tests/ui/duplicate_symbol.rs");
+    t.compile_fail("tests/ui/result_char.rs");
+    t.compile_fail("tests/ui/option_enum_without_zero.rs");
+    t.compile_fail("tests/ui/thread_safe_rc.rs");
+    t.compile_fail("tests/ui/trait_impl.rs");
+    t.compile_fail("tests/ui/non_ffi_const.rs");
+    t.compile_fail("tests/ui/option_zero_sized.rs");
+    t.compile_fail("tests/ui/static_str_non_literal.rs");
+    t.compile_fail("tests/ui/field_rename_unknown.rs");
+    t.compile_fail("tests/ui/generic_impl.rs");
+    t.compile_fail("tests/ui/option_nonzero.rs");
+    t.compile_fail("tests/ui/nested_not_julia.rs");
+    t.compile_fail("tests/ui/iterator_fn_pointer.rs");
+    t.compile_fail("tests/ui/canonical_bad_type.rs");
+    t.compile_fail("tests/ui/canonical_undeclared.rs");
+    t.compile_fail("tests/ui/abi_on_struct.rs");
+    t.compile_fail("tests/ui/method_helper_clash.rs");
+    t.pass("tests/ui/mut_param.rs");
+    t.pass("tests/ui/cfg_result.rs");
+    t.pass("tests/ui/canonical_cfg.rs");
 }
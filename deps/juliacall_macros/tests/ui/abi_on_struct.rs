@@ -0,0 +1,20 @@
+use juliacall_macros::julia;
+
+// abi only applies to function wrappers; struct accessors would silently stay extern "C"
+#[julia(abi = "C-unwind")]
+pub struct Reading {
+    pub value: f64,
+}
+
+pub struct Sensor;
+
+// Function options are rejected on methods too
+#[julia]
+impl Sensor {
+    #[julia(errno)]
+    pub fn read(&self) -> f64 {
+        1.0
+    }
+}
+
+fn main() {}
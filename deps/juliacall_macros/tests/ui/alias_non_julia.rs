@@ -0,0 +1,11 @@
+use juliacall_macros::julia;
+
+// Aliases can only re-export the FFI surface of #[julia] structs
+pub struct Plain {
+    pub x: f64,
+}
+
+#[julia]
+pub type Alias = Plain;
+
+fn main() {}
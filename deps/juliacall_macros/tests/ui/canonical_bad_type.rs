@@ -0,0 +1,4 @@
+// julia_canonical! only declares Result and Option types
+juliacall_macros::julia_canonical!(Result<f64, i32>, Vec<f64>);
+
+fn main() {}
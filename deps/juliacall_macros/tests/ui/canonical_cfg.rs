@@ -0,0 +1,22 @@
+#![deny(warnings)]
+
+use juliacall_macros::julia;
+
+juliacall_macros::julia_canonical!(Result<f64, i32>);
+
+// The first function using the canonical type is compiled out; the type is still defined
+#[julia(canonical)]
+#[cfg(not(target_pointer_width = "64"))]
+fn narrow_sqrt(x: f64) -> Result<f64, i32> {
+    if x < 0.0 { Err(-1) } else { Ok(x.sqrt()) }
+}
+
+#[julia(canonical)]
+fn wide_sqrt(x: f64) -> Result<f64, i32> {
+    if x < 0.0 { Err(-1) } else { Ok(x.sqrt()) }
+}
+
+fn main() {
+    let result: CResult_f64_i32 = wide_sqrt(4.0);
+    assert_eq!(result.is_ok, 1);
+}
@@ -0,0 +1,9 @@
+use juliacall_macros::julia;
+
+// The canonical type must be declared with julia_canonical!
+#[julia(canonical)]
+fn checked_recip(x: f64) -> Result<f64, i32> {
+    if x == 0.0 { Err(-1) } else { Ok(1.0 / x) }
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+#![deny(warnings)]
+
+use juliacall_macros::julia;
+
+#[cfg(target_pointer_width = "64")]
+fn checked_width(bytes: usize) -> Result<u32, i32> {
+    if bytes == 8 { Ok(64) } else { Err(-1) }
+}
+
+// Mutually exclusive definitions: the generated inner fn, CResult type and wrapper
+// must all be gated by the same #[cfg] as the user's function
+#[julia]
+#[cfg(target_pointer_width = "64")]
+fn pointer_bits(bytes: usize) -> Result<u32, i32> {
+    checked_width(bytes)
+}
+
+#[julia]
+#[cfg(not(target_pointer_width = "64"))]
+fn pointer_bits(bytes: usize) -> Result<u32, i32> {
+    Err(bytes as i32)
+}
+
+fn main() {
+    assert_eq!(pointer_bits(std::mem::size_of::<usize>()).is_ok, 1);
+}
@@ -0,0 +1,9 @@
+use juliacall_macros::julia;
+
+// Destructuring patterns have no name the extern wrapper could forward
+#[julia]
+fn sum_pair((a, b): (i32, i32)) -> Option<i32> {
+    a.checked_add(b)
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use juliacall_macros::julia;
+
+// Same-named #[julia] functions would export the same #[no_mangle] symbol
+mod first {
+    use super::*;
+
+    #[julia]
+    fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+}
+
+mod second {
+    use super::*;
+
+    #[julia]
+    fn add(a: f64, b: f64) -> f64 {
+        a + b
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use juliacall_macros::julia;
+
+// Fields accept only the rename option
+#[julia]
+pub struct Sample {
+    #[julia(alias = "value")]
+    pub raw: f64,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use juliacall_macros::julia;
+
+pub struct Wrapper<T> {
+    pub value: T,
+}
+
+// The wrapper would be exported as `Wrapper_new` and call `Wrapper::new`,
+// losing the `<i32>` specialization
+#[julia]
+impl Wrapper<i32> {
+    #[julia]
+    pub fn new(value: i32) -> Self {
+        Wrapper { value }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use juliacall_macros::julia;
+
+// An exhausted iterator zero-fills the item, which is not a valid fn pointer
+#[julia(iterator = "extern \"C\" fn()")]
+pub struct Callbacks {
+    pub remaining: u32,
+}
+
+fn main() {}
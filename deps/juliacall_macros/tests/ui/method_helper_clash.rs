@@ -0,0 +1,18 @@
+use juliacall_macros::julia;
+
+#[julia]
+pub struct Pair {
+    pub a: i32,
+    pub b: i32,
+}
+
+// Pair_swap is already generated for the struct
+#[julia]
+impl Pair {
+    #[julia]
+    pub fn swap(&mut self) {
+        std::mem::swap(&mut self.a, &mut self.b);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use juliacall_macros::julia;
+
+// Methods returning borrows would hand Julia a reference with no lifetime
+#[julia]
+pub struct Named {
+    pub id: i32,
+}
+
+#[julia]
+impl Named {
+    #[julia]
+    pub fn id_ref(&self) -> &i32 {
+        &self.id
+    }
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+#![deny(warnings)]
+
+use juliacall_macros::julia;
+
+// `mut` parameters are stripped from the extern signature without unused_mut warnings
+#[julia]
+fn countdown(mut n: i32) -> Option<i32> {
+    while n > 10 {
+        n -= 10;
+    }
+    if n > 0 {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+#[julia]
+fn scaled_sum(mut scale: f64, xs: &[f64]) -> f64 {
+    scale *= 2.0;
+    xs.iter().sum::<f64>() * scale
+}
+
+fn main() {
+    assert_eq!(countdown(25).value, 5);
+    let xs = [1.0, 2.0];
+    assert_eq!(scaled_sum(0.5, xs.as_ptr(), xs.len()), 3.0);
+}
@@ -0,0 +1,12 @@
+use juliacall_macros::julia;
+use std::time::Duration;
+
+// Duration is not a #[julia] struct, so it cannot be accessed as a nested field
+#[julia]
+pub struct Timed {
+    #[julia(nested)]
+    pub elapsed: Duration,
+    pub runs: u32,
+}
+
+fn main() {}
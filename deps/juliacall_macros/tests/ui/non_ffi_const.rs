@@ -0,0 +1,7 @@
+use juliacall_macros::julia;
+
+// Constants are returned by value, so their type must be FFI-compatible
+#[julia]
+pub const GREETING: &str = "hello";
+
+fn main() {}
@@ -0,0 +1,10 @@
+use juliacall_macros::julia;
+
+// verify_repr_c rejects fields whose layout is not repr(C)-safe
+#[julia(verify_repr_c)]
+pub struct Config {
+    pub id: i32,
+    pub name: String,
+}
+
+fn main() {}
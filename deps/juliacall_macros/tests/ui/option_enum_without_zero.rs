@@ -0,0 +1,19 @@
+use juliacall_macros::julia;
+
+#[julia(repr = "i32")]
+pub enum Level {
+    Low = 1,
+    High = 2,
+}
+
+// None zero-fills the payload, which is not a declared `Level` discriminant
+#[julia]
+fn parse_level(code: i32) -> Option<Level> {
+    match code {
+        1 => Some(Level::Low),
+        2 => Some(Level::High),
+        _ => None,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use juliacall_macros::julia;
+#[allow(unused_imports)]
+use std::num::NonZeroU32;
+
+// NonZeroU32 has no all-zero value for the unused None variant
+#[julia]
+fn parse_id(raw: u32) -> Option<NonZeroU32> {
+    NonZeroU32::new(raw)
+}
+
+fn main() {}
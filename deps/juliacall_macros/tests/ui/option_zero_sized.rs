@@ -0,0 +1,15 @@
+use juliacall_macros::julia;
+
+// Zero-sized payloads have no C representation inside COption/CResult
+pub struct Token;
+
+#[julia]
+fn take_token(available: bool) -> Option<Token> {
+    if available {
+        Some(Token)
+    } else {
+        None
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use juliacall_macros::julia;
+
+// char has no valid all-zero value, so the unused Ok payload cannot be zero-filled
+#[julia]
+fn first_char(text: &[u8]) -> Result<char, i32> {
+    text.first().map(|&b| b as char).ok_or(-1)
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use juliacall_macros::julia;
+
+// Only a single string literal can be baked into a static C string
+#[julia]
+fn build_name(debug: bool) -> &'static str {
+    if debug {
+        "debug"
+    } else {
+        "release"
+    }
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use juliacall_macros::julia;
+use std::rc::Rc;
+
+// thread_safe rejects structs that are not Send + Sync
+#[julia(thread_safe)]
+pub struct SharedCache {
+    pub hits: u64,
+    pub entries: Rc<Vec<f64>>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use juliacall_macros::julia;
+
+// Only inherent impls can be exported; trait methods would clash across types
+#[julia]
+pub struct Meters {
+    pub value: f64,
+}
+
+#[julia]
+impl std::fmt::Display for Meters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} m", self.value)
+    }
+}
+
+fn main() {}
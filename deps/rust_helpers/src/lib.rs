@@ -1,8 +1,8 @@
 // Rust helper functions for RustCall.jl ownership types
 // These functions provide FFI-safe wrappers for Box, Rc, and Arc
 
-use std::ffi::c_void;
-use std::sync::Arc;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::{Arc, Weak};
 use std::rc::Rc;
 
 // ============================================================================
@@ -198,6 +198,118 @@ pub unsafe extern "C" fn rust_rc_drop_f64(ptr: *mut c_void) {
     }
 }
 
+/// Get the strong reference count of an Rc<i32> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_i32`/`rust_rc_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_strong_count_i32(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const i32);
+    let count = Rc::strong_count(&rc);
+    std::mem::forget(rc);  // Don't decrement the count we just read
+    count
+}
+
+/// Get the weak reference count of an Rc<i32> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_i32`/`rust_rc_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_weak_count_i32(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const i32);
+    let count = Rc::weak_count(&rc);
+    std::mem::forget(rc);
+    count
+}
+
+/// Get the strong reference count of an Rc<i64> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_i64`/`rust_rc_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_strong_count_i64(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const i64);
+    let count = Rc::strong_count(&rc);
+    std::mem::forget(rc);  // Don't decrement the count we just read
+    count
+}
+
+/// Get the weak reference count of an Rc<i64> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_i64`/`rust_rc_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_weak_count_i64(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const i64);
+    let count = Rc::weak_count(&rc);
+    std::mem::forget(rc);
+    count
+}
+
+/// Get the strong reference count of an Rc<f32> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_f32`/`rust_rc_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_strong_count_f32(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const f32);
+    let count = Rc::strong_count(&rc);
+    std::mem::forget(rc);  // Don't decrement the count we just read
+    count
+}
+
+/// Get the weak reference count of an Rc<f32> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_f32`/`rust_rc_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_weak_count_f32(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const f32);
+    let count = Rc::weak_count(&rc);
+    std::mem::forget(rc);
+    count
+}
+
+/// Get the strong reference count of an Rc<f64> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_f64`/`rust_rc_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_strong_count_f64(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const f64);
+    let count = Rc::strong_count(&rc);
+    std::mem::forget(rc);  // Don't decrement the count we just read
+    count
+}
+
+/// Get the weak reference count of an Rc<f64> (0 for null)
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_rc_new_f64`/`rust_rc_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_rc_weak_count_f64(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let rc = Rc::from_raw(ptr as *const f64);
+    let count = Rc::weak_count(&rc);
+    std::mem::forget(rc);
+    count
+}
+
 // ============================================================================
 // Arc<T> helpers (thread-safe atomic reference counting)
 // ============================================================================
@@ -307,6 +419,249 @@ pub unsafe extern "C" fn rust_arc_drop_f64(ptr: *mut c_void) {
     }
 }
 
+/// Check whether two Arc<i32> handles point to the same allocation
+/// Returns false if either pointer is null
+/// # Safety
+/// `a` and `b` must be null or pointers returned by `rust_arc_new_i32`/`rust_arc_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_ptr_eq_i32(a: *mut c_void, b: *mut c_void) -> bool {
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+    let arc_a = Arc::from_raw(a as *const i32);
+    let arc_b = Arc::from_raw(b as *const i32);
+    let same = Arc::ptr_eq(&arc_a, &arc_b);
+    std::mem::forget(arc_a);  // Both handles stay owned by the caller
+    std::mem::forget(arc_b);
+    same
+}
+
+/// Check whether two Arc<i64> handles point to the same allocation
+/// Returns false if either pointer is null
+/// # Safety
+/// `a` and `b` must be null or pointers returned by `rust_arc_new_i64`/`rust_arc_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_ptr_eq_i64(a: *mut c_void, b: *mut c_void) -> bool {
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+    let arc_a = Arc::from_raw(a as *const i64);
+    let arc_b = Arc::from_raw(b as *const i64);
+    let same = Arc::ptr_eq(&arc_a, &arc_b);
+    std::mem::forget(arc_a);  // Both handles stay owned by the caller
+    std::mem::forget(arc_b);
+    same
+}
+
+/// Check whether two Arc<f32> handles point to the same allocation
+/// Returns false if either pointer is null
+/// # Safety
+/// `a` and `b` must be null or pointers returned by `rust_arc_new_f32`/`rust_arc_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_ptr_eq_f32(a: *mut c_void, b: *mut c_void) -> bool {
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+    let arc_a = Arc::from_raw(a as *const f32);
+    let arc_b = Arc::from_raw(b as *const f32);
+    let same = Arc::ptr_eq(&arc_a, &arc_b);
+    std::mem::forget(arc_a);  // Both handles stay owned by the caller
+    std::mem::forget(arc_b);
+    same
+}
+
+/// Check whether two Arc<f64> handles point to the same allocation
+/// Returns false if either pointer is null
+/// # Safety
+/// `a` and `b` must be null or pointers returned by `rust_arc_new_f64`/`rust_arc_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_ptr_eq_f64(a: *mut c_void, b: *mut c_void) -> bool {
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+    let arc_a = Arc::from_raw(a as *const f64);
+    let arc_b = Arc::from_raw(b as *const f64);
+    let same = Arc::ptr_eq(&arc_a, &arc_b);
+    std::mem::forget(arc_a);  // Both handles stay owned by the caller
+    std::mem::forget(arc_b);
+    same
+}
+
+// Weak<T> handles come from `Weak::into_raw` and are distinct from Arc handles: they
+// keep the allocation (not the value) alive and must be released with `rust_weak_drop_*`.
+
+/// Create a Weak<i32> from an Arc<i32> without consuming the Arc
+/// Returns null if the pointer is null
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_new_i32`/`rust_arc_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_downgrade_i32(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let arc = Arc::from_raw(ptr as *const i32);
+    let weak = Arc::downgrade(&arc);
+    std::mem::forget(arc);  // Keep original reference alive
+    Weak::into_raw(weak) as *mut c_void
+}
+
+/// Upgrade a Weak<i32> to a new Arc<i32> handle
+/// Returns null if the pointer is null or the value has already been dropped
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_upgrade_i32(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let weak = Weak::from_raw(ptr as *const i32);
+    let upgraded = weak.upgrade();
+    std::mem::forget(weak);  // The weak handle stays owned by the caller
+    match upgraded {
+        Some(arc) => Arc::into_raw(arc) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Drop a Weak<i32>
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_drop_i32(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = Weak::from_raw(ptr as *const i32);
+    }
+}
+
+/// Create a Weak<i64> from an Arc<i64> without consuming the Arc
+/// Returns null if the pointer is null
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_new_i64`/`rust_arc_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_downgrade_i64(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let arc = Arc::from_raw(ptr as *const i64);
+    let weak = Arc::downgrade(&arc);
+    std::mem::forget(arc);  // Keep original reference alive
+    Weak::into_raw(weak) as *mut c_void
+}
+
+/// Upgrade a Weak<i64> to a new Arc<i64> handle
+/// Returns null if the pointer is null or the value has already been dropped
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_upgrade_i64(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let weak = Weak::from_raw(ptr as *const i64);
+    let upgraded = weak.upgrade();
+    std::mem::forget(weak);  // The weak handle stays owned by the caller
+    match upgraded {
+        Some(arc) => Arc::into_raw(arc) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Drop a Weak<i64>
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_drop_i64(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = Weak::from_raw(ptr as *const i64);
+    }
+}
+
+/// Create a Weak<f32> from an Arc<f32> without consuming the Arc
+/// Returns null if the pointer is null
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_new_f32`/`rust_arc_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_downgrade_f32(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let arc = Arc::from_raw(ptr as *const f32);
+    let weak = Arc::downgrade(&arc);
+    std::mem::forget(arc);  // Keep original reference alive
+    Weak::into_raw(weak) as *mut c_void
+}
+
+/// Upgrade a Weak<f32> to a new Arc<f32> handle
+/// Returns null if the pointer is null or the value has already been dropped
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_upgrade_f32(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let weak = Weak::from_raw(ptr as *const f32);
+    let upgraded = weak.upgrade();
+    std::mem::forget(weak);  // The weak handle stays owned by the caller
+    match upgraded {
+        Some(arc) => Arc::into_raw(arc) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Drop a Weak<f32>
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_drop_f32(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = Weak::from_raw(ptr as *const f32);
+    }
+}
+
+/// Create a Weak<f64> from an Arc<f64> without consuming the Arc
+/// Returns null if the pointer is null
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_new_f64`/`rust_arc_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_downgrade_f64(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let arc = Arc::from_raw(ptr as *const f64);
+    let weak = Arc::downgrade(&arc);
+    std::mem::forget(arc);  // Keep original reference alive
+    Weak::into_raw(weak) as *mut c_void
+}
+
+/// Upgrade a Weak<f64> to a new Arc<f64> handle
+/// Returns null if the pointer is null or the value has already been dropped
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_upgrade_f64(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let weak = Weak::from_raw(ptr as *const f64);
+    let upgraded = weak.upgrade();
+    std::mem::forget(weak);  // The weak handle stays owned by the caller
+    match upgraded {
+        Some(arc) => Arc::into_raw(arc) as *mut c_void,
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Drop a Weak<f64>
+/// # Safety
+/// `ptr` must be null or a pointer returned by `rust_arc_downgrade_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_weak_drop_f64(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = Weak::from_raw(ptr as *const f64);
+    }
+}
+
 // ============================================================================
 // Vec<T> helpers
 // ============================================================================
@@ -363,6 +718,16 @@ pub unsafe extern "C" fn rust_vec_drop_f64(vec: CVec) {
     }
 }
 
+/// Drop a Vec<bool>
+/// # Safety
+/// `vec` must be a CVec of bool created by these helpers, not used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_drop_bool(vec: CVec) {
+    if !vec.ptr.is_null() && vec.cap > 0 {
+        let _ = Vec::from_raw_parts(vec.ptr as *mut bool, vec.len, vec.cap);
+    }
+}
+
 /// Create a Vec<i32> from a C array
 /// # Safety
 /// The caller must ensure that `data` points to a valid array of at least `len` elements
@@ -460,6 +825,75 @@ pub unsafe extern "C" fn rust_vec_new_from_array_f64(data: *const f64, len: usiz
     CVec { ptr, len, cap }
 }
 
+/// Create a Vec<bool> from a C array (Julia `Bool` is also one byte)
+/// Bytes are read as `u8` and any non-zero value becomes `true`
+/// # Safety
+/// The caller must ensure that `data` points to a valid array of at least `len` elements
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_new_from_array_bool(data: *const bool, len: usize) -> CVec {
+    if data.is_null() || len == 0 {
+        return CVec {
+            ptr: std::ptr::null_mut(),
+            len: 0,
+            cap: 0,
+        };
+    }
+
+    let slice = std::slice::from_raw_parts(data as *const u8, len);
+    let vec: Vec<bool> = slice.iter().map(|&byte| byte != 0).collect();
+
+    let len = vec.len();
+    let cap = vec.capacity();
+    let ptr = vec.as_ptr() as *mut c_void;
+    std::mem::forget(vec);
+
+    CVec { ptr, len, cap }
+}
+
+/// Create a Vec<i32> of `len` copies of `value`
+#[no_mangle]
+pub extern "C" fn rust_vec_fill_i32(len: usize, value: i32) -> CVec {
+    let vec: Vec<i32> = vec![value; len];
+    let len = vec.len();
+    let cap = vec.capacity();
+    let ptr = vec.as_ptr() as *mut c_void;
+    std::mem::forget(vec);  // Transfer ownership to caller
+    CVec { ptr, len, cap }
+}
+
+/// Create a Vec<i64> of `len` copies of `value`
+#[no_mangle]
+pub extern "C" fn rust_vec_fill_i64(len: usize, value: i64) -> CVec {
+    let vec: Vec<i64> = vec![value; len];
+    let len = vec.len();
+    let cap = vec.capacity();
+    let ptr = vec.as_ptr() as *mut c_void;
+    std::mem::forget(vec);  // Transfer ownership to caller
+    CVec { ptr, len, cap }
+}
+
+/// Create a Vec<f32> of `len` copies of `value`
+#[no_mangle]
+pub extern "C" fn rust_vec_fill_f32(len: usize, value: f32) -> CVec {
+    let vec: Vec<f32> = vec![value; len];
+    let len = vec.len();
+    let cap = vec.capacity();
+    let ptr = vec.as_ptr() as *mut c_void;
+    std::mem::forget(vec);  // Transfer ownership to caller
+    CVec { ptr, len, cap }
+}
+
+/// Create a Vec<f64> of `len` copies of `value`
+#[no_mangle]
+pub extern "C" fn rust_vec_fill_f64(len: usize, value: f64) -> CVec {
+    let vec: Vec<f64> = vec![value; len];
+    let len = vec.len();
+    let cap = vec.capacity();
+    let ptr = vec.as_ptr() as *mut c_void;
+    std::mem::forget(vec);  // Transfer ownership to caller
+    CVec { ptr, len, cap }
+}
+
 // ============================================================================
 // Vec<T> element access
 // ============================================================================
@@ -505,6 +939,18 @@ pub unsafe extern "C" fn rust_vec_get_f64(vec: CVec, index: usize) -> f64 {
     slice[index]
 }
 
+/// Get an element from Vec<bool> by index
+/// # Safety
+/// `vec` must be a CVec of bool created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_get_bool(vec: CVec, index: usize) -> bool {
+    if vec.ptr.is_null() || index >= vec.len {
+        return false;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const bool, vec.len);
+    slice[index]
+}
+
 /// Set an element in Vec<i32> by index
 /// Returns true if successful, false if index is out of bounds
 #[no_mangle]
@@ -550,11 +996,24 @@ pub unsafe extern "C" fn rust_vec_set_f64(vec: CVec, index: usize, value: f64) -
     true
 }
 
-// ============================================================================
-// Vec<T> copy to C array
-// ============================================================================
-
-/// Copy Vec<i32> contents to a C array
+/// Set an element in Vec<bool> by index
+/// # Safety
+/// `vec` must be a CVec of bool created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_set_bool(vec: CVec, index: usize, value: bool) -> bool {
+    if vec.ptr.is_null() || index >= vec.len {
+        return false;
+    }
+    let slice = std::slice::from_raw_parts_mut(vec.ptr as *mut bool, vec.len);
+    slice[index] = value;
+    true
+}
+
+// ============================================================================
+// Vec<T> copy to C array
+// ============================================================================
+
+/// Copy Vec<i32> contents to a C array
 /// Returns the number of elements copied
 #[no_mangle]
 pub unsafe extern "C" fn rust_vec_copy_to_array_i32(vec: CVec, dest: *mut i32, dest_len: usize) -> usize {
@@ -607,6 +1066,22 @@ pub unsafe extern "C" fn rust_vec_copy_to_array_f64(vec: CVec, dest: *mut f64, d
     copy_len
 }
 
+/// Copy Vec<bool> contents to a C array
+/// # Safety
+/// `vec` must be a CVec of bool created by these helpers and `dest` must point to
+/// at least `dest_len` writable elements
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_copy_to_array_bool(vec: CVec, dest: *mut bool, dest_len: usize) -> usize {
+    if vec.ptr.is_null() || dest.is_null() {
+        return 0;
+    }
+    let copy_len = std::cmp::min(vec.len, dest_len);
+    let src_slice = std::slice::from_raw_parts(vec.ptr as *const bool, copy_len);
+    let dest_slice = std::slice::from_raw_parts_mut(dest, copy_len);
+    dest_slice.copy_from_slice(src_slice);
+    copy_len
+}
+
 // ============================================================================
 // Vec<T> push operations
 // ============================================================================
@@ -700,3 +1175,2098 @@ pub unsafe extern "C" fn rust_vec_push_f64(vec: CVec, value: f64) -> CVec {
     std::mem::forget(v);
     CVec { ptr, len, cap }
 }
+
+/// Push a value to Vec<bool>
+/// # Safety
+/// `vec` must be a CVec of bool created by these helpers; it is consumed
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_push_bool(vec: CVec, value: bool) -> CVec {
+    if vec.ptr.is_null() {
+        let new_vec = vec![value];
+        let len = new_vec.len();
+        let cap = new_vec.capacity();
+        let ptr = new_vec.as_ptr() as *mut c_void;
+        std::mem::forget(new_vec);
+        return CVec { ptr, len, cap };
+    }
+
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut bool, vec.len, vec.cap);
+    v.push(value);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+// ============================================================================
+// Vec<T> positional insert/remove
+// ============================================================================
+//
+// An out-of-bounds index leaves the vec unchanged: `insert` then returns the same
+// length, and `remove` reports `ok = 0`.
+
+/// Result of `rust_vec_remove_i32`: the new vec plus the removed value
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecPop_i32 {
+    pub vec: CVec,
+    pub value: i32,
+    /// 1 if an element was removed, 0 if the index was out of bounds
+    pub ok: u8,
+}
+
+/// Insert a value into Vec<i32> at `index`, shifting later elements right
+/// Returns a new CVec (the original vec is consumed); unchanged if `index > len`
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_insert_i32(vec: CVec, index: usize, value: i32) -> CVec {
+    if index > vec.len {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap)
+    };
+    v.insert(index, value);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Remove the value at `index` from Vec<i32>, shifting later elements left
+/// Returns the new CVec (the original vec is consumed) and the removed value
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_remove_i32(vec: CVec, index: usize) -> CVecPop_i32 {
+    if vec.ptr.is_null() || index >= vec.len {
+        return CVecPop_i32 { vec, value: 0, ok: 0 };
+    }
+
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap);
+    let value = v.remove(index);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVecPop_i32 { vec: CVec { ptr, len, cap }, value, ok: 1 }
+}
+
+/// Result of `rust_vec_remove_i64`: the new vec plus the removed value
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecPop_i64 {
+    pub vec: CVec,
+    pub value: i64,
+    /// 1 if an element was removed, 0 if the index was out of bounds
+    pub ok: u8,
+}
+
+/// Insert a value into Vec<i64> at `index`, shifting later elements right
+/// Returns a new CVec (the original vec is consumed); unchanged if `index > len`
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_insert_i64(vec: CVec, index: usize, value: i64) -> CVec {
+    if index > vec.len {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap)
+    };
+    v.insert(index, value);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Remove the value at `index` from Vec<i64>, shifting later elements left
+/// Returns the new CVec (the original vec is consumed) and the removed value
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_remove_i64(vec: CVec, index: usize) -> CVecPop_i64 {
+    if vec.ptr.is_null() || index >= vec.len {
+        return CVecPop_i64 { vec, value: 0, ok: 0 };
+    }
+
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap);
+    let value = v.remove(index);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVecPop_i64 { vec: CVec { ptr, len, cap }, value, ok: 1 }
+}
+
+/// Result of `rust_vec_remove_f32`: the new vec plus the removed value
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecPop_f32 {
+    pub vec: CVec,
+    pub value: f32,
+    /// 1 if an element was removed, 0 if the index was out of bounds
+    pub ok: u8,
+}
+
+/// Insert a value into Vec<f32> at `index`, shifting later elements right
+/// Returns a new CVec (the original vec is consumed); unchanged if `index > len`
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_insert_f32(vec: CVec, index: usize, value: f32) -> CVec {
+    if index > vec.len {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap)
+    };
+    v.insert(index, value);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Remove the value at `index` from Vec<f32>, shifting later elements left
+/// Returns the new CVec (the original vec is consumed) and the removed value
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_remove_f32(vec: CVec, index: usize) -> CVecPop_f32 {
+    if vec.ptr.is_null() || index >= vec.len {
+        return CVecPop_f32 { vec, value: 0.0, ok: 0 };
+    }
+
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap);
+    let value = v.remove(index);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVecPop_f32 { vec: CVec { ptr, len, cap }, value, ok: 1 }
+}
+
+/// Result of `rust_vec_remove_f64`: the new vec plus the removed value
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecPop_f64 {
+    pub vec: CVec,
+    pub value: f64,
+    /// 1 if an element was removed, 0 if the index was out of bounds
+    pub ok: u8,
+}
+
+/// Insert a value into Vec<f64> at `index`, shifting later elements right
+/// Returns a new CVec (the original vec is consumed); unchanged if `index > len`
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_insert_f64(vec: CVec, index: usize, value: f64) -> CVec {
+    if index > vec.len {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap)
+    };
+    v.insert(index, value);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Remove the value at `index` from Vec<f64>, shifting later elements left
+/// Returns the new CVec (the original vec is consumed) and the removed value
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_remove_f64(vec: CVec, index: usize) -> CVecPop_f64 {
+    if vec.ptr.is_null() || index >= vec.len {
+        return CVecPop_f64 { vec, value: 0.0, ok: 0 };
+    }
+
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap);
+    let value = v.remove(index);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVecPop_f64 { vec: CVec { ptr, len, cap }, value, ok: 1 }
+}
+
+// ============================================================================
+// Vec<T> extend operations (bulk append)
+// ============================================================================
+
+/// Append `len` values from a C array to Vec<i32>, reconstructing the Vec only once
+/// Returns a new CVec (the original vec is consumed)
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers; `data` must point to `len` values
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_extend_from_array_i32(vec: CVec, data: *const i32, len: usize) -> CVec {
+    if data.is_null() || len == 0 {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::with_capacity(len)
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap)
+    };
+    v.extend_from_slice(std::slice::from_raw_parts(data, len));
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Append `len` values from a C array to Vec<i64>, reconstructing the Vec only once
+/// Returns a new CVec (the original vec is consumed)
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers; `data` must point to `len` values
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_extend_from_array_i64(vec: CVec, data: *const i64, len: usize) -> CVec {
+    if data.is_null() || len == 0 {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::with_capacity(len)
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap)
+    };
+    v.extend_from_slice(std::slice::from_raw_parts(data, len));
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Append `len` values from a C array to Vec<f32>, reconstructing the Vec only once
+/// Returns a new CVec (the original vec is consumed)
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers; `data` must point to `len` values
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_extend_from_array_f32(vec: CVec, data: *const f32, len: usize) -> CVec {
+    if data.is_null() || len == 0 {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::with_capacity(len)
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap)
+    };
+    v.extend_from_slice(std::slice::from_raw_parts(data, len));
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Append `len` values from a C array to Vec<f64>, reconstructing the Vec only once
+/// Returns a new CVec (the original vec is consumed)
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers; `data` must point to `len` values
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_extend_from_array_f64(vec: CVec, data: *const f64, len: usize) -> CVec {
+    if data.is_null() || len == 0 {
+        return vec;
+    }
+
+    let mut v = if vec.ptr.is_null() {
+        Vec::with_capacity(len)
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap)
+    };
+    v.extend_from_slice(std::slice::from_raw_parts(data, len));
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+// ============================================================================
+// Vec<T> raw data access (zero-copy)
+// ============================================================================
+
+/// Get the raw data pointer of a Vec<i32> for zero-copy reads
+///
+/// This is the `ptr` field of the CVec, typed for element reads; together with `len`
+/// it is the view Julia passes to `unsafe_wrap`.
+/// The pointer borrows the Vec's buffer: it is invalidated by any operation that
+/// may reallocate or free the Vec (push, drop, ...). The caller must not write
+/// through it or keep it beyond the Vec's lifetime.
+/// For an empty Vec the pointer is non-null but dangling: it is only valid as a
+/// zero-length view and must not be dereferenced.
+#[no_mangle]
+pub extern "C" fn rust_vec_as_ptr_i32(vec: CVec) -> *const i32 {
+    vec.ptr as *const i32
+}
+
+/// Get the raw data pointer of a Vec<i64> for zero-copy reads
+/// See `rust_vec_as_ptr_i32` for the lifetime constraints.
+#[no_mangle]
+pub extern "C" fn rust_vec_as_ptr_i64(vec: CVec) -> *const i64 {
+    vec.ptr as *const i64
+}
+
+/// Get the raw data pointer of a Vec<f32> for zero-copy reads
+/// See `rust_vec_as_ptr_i32` for the lifetime constraints.
+#[no_mangle]
+pub extern "C" fn rust_vec_as_ptr_f32(vec: CVec) -> *const f32 {
+    vec.ptr as *const f32
+}
+
+/// Get the raw data pointer of a Vec<f64> for zero-copy reads
+/// See `rust_vec_as_ptr_i32` for the lifetime constraints.
+#[no_mangle]
+pub extern "C" fn rust_vec_as_ptr_f64(vec: CVec) -> *const f64 {
+    vec.ptr as *const f64
+}
+
+// ============================================================================
+// Vec<T> in-place arithmetic (bulk operations)
+// ============================================================================
+//
+// These keep hot loops on the Rust side: one call updates every element instead of
+// one `rust_vec_get`/`rust_vec_set` round trip per element. The vec is borrowed, not
+// consumed, and an empty vec is a no-op.
+
+/// Multiply every element of Vec<f32> by `factor` in place
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_scale_f32(vec: CVec, factor: f32) {
+    if vec.ptr.is_null() {
+        return;
+    }
+    let slice = std::slice::from_raw_parts_mut(vec.ptr as *mut f32, vec.len);
+    slice.iter_mut().for_each(|x| *x *= factor);
+}
+
+/// Multiply every element of Vec<f64> by `factor` in place
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_scale_f64(vec: CVec, factor: f64) {
+    if vec.ptr.is_null() {
+        return;
+    }
+    let slice = std::slice::from_raw_parts_mut(vec.ptr as *mut f64, vec.len);
+    slice.iter_mut().for_each(|x| *x *= factor);
+}
+
+/// Add `value` to every element of Vec<f32> in place
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_add_scalar_f32(vec: CVec, value: f32) {
+    if vec.ptr.is_null() {
+        return;
+    }
+    let slice = std::slice::from_raw_parts_mut(vec.ptr as *mut f32, vec.len);
+    slice.iter_mut().for_each(|x| *x += value);
+}
+
+/// Add `value` to every element of Vec<f64> in place
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_add_scalar_f64(vec: CVec, value: f64) {
+    if vec.ptr.is_null() {
+        return;
+    }
+    let slice = std::slice::from_raw_parts_mut(vec.ptr as *mut f64, vec.len);
+    slice.iter_mut().for_each(|x| *x += value);
+}
+
+// ============================================================================
+// Vec<T> element-wise combination
+// ============================================================================
+//
+// Both inputs are borrowed read-only and the result is a new vec as long as the
+// shorter input, which the caller drops with `rust_vec_drop_f64`.
+
+/// Element-wise sum of two Vec<f64>
+/// # Safety
+/// `a` and `b` must be CVecs of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_zip_add_f64(a: CVec, b: CVec) -> CVec {
+    if a.ptr.is_null() || b.ptr.is_null() {
+        return CVec { ptr: std::ptr::null_mut(), len: 0, cap: 0 };
+    }
+    let a = std::slice::from_raw_parts(a.ptr as *const f64, a.len);
+    let b = std::slice::from_raw_parts(b.ptr as *const f64, b.len);
+    let v: Vec<f64> = a.iter().zip(b).map(|(x, y)| x + y).collect();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Element-wise product of two Vec<f64>
+/// # Safety
+/// `a` and `b` must be CVecs of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_zip_mul_f64(a: CVec, b: CVec) -> CVec {
+    if a.ptr.is_null() || b.ptr.is_null() {
+        return CVec { ptr: std::ptr::null_mut(), len: 0, cap: 0 };
+    }
+    let a = std::slice::from_raw_parts(a.ptr as *const f64, a.len);
+    let b = std::slice::from_raw_parts(b.ptr as *const f64, b.len);
+    let v: Vec<f64> = a.iter().zip(b).map(|(x, y)| x * y).collect();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+// ============================================================================
+// Vec<T> search
+// ============================================================================
+//
+// Elements are compared with `==`, so a NaN needle is never found.
+
+/// Check whether Vec<i32> contains `needle`
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_contains_i32(vec: CVec, needle: i32) -> bool {
+    rust_vec_index_of_i32(vec, needle) >= 0
+}
+
+/// Find the index of the first element of Vec<i32> equal to `needle`
+/// Returns -1 if absent
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_index_of_i32(vec: CVec, needle: i32) -> isize {
+    if vec.ptr.is_null() {
+        return -1;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i32, vec.len);
+    slice.iter().position(|&x| x == needle).map_or(-1, |i| i as isize)
+}
+
+/// Check whether Vec<i64> contains `needle`
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_contains_i64(vec: CVec, needle: i64) -> bool {
+    rust_vec_index_of_i64(vec, needle) >= 0
+}
+
+/// Find the index of the first element of Vec<i64> equal to `needle`
+/// Returns -1 if absent
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_index_of_i64(vec: CVec, needle: i64) -> isize {
+    if vec.ptr.is_null() {
+        return -1;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i64, vec.len);
+    slice.iter().position(|&x| x == needle).map_or(-1, |i| i as isize)
+}
+
+/// Check whether Vec<f32> contains `needle`
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_contains_f32(vec: CVec, needle: f32) -> bool {
+    rust_vec_index_of_f32(vec, needle) >= 0
+}
+
+/// Find the index of the first element of Vec<f32> equal to `needle`
+/// Returns -1 if absent
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_index_of_f32(vec: CVec, needle: f32) -> isize {
+    if vec.ptr.is_null() {
+        return -1;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f32, vec.len);
+    slice.iter().position(|&x| x == needle).map_or(-1, |i| i as isize)
+}
+
+/// Check whether Vec<f64> contains `needle`
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_contains_f64(vec: CVec, needle: f64) -> bool {
+    rust_vec_index_of_f64(vec, needle) >= 0
+}
+
+/// Find the index of the first element of Vec<f64> equal to `needle`
+/// Returns -1 if absent
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_index_of_f64(vec: CVec, needle: f64) -> isize {
+    if vec.ptr.is_null() {
+        return -1;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f64, vec.len);
+    slice.iter().position(|&x| x == needle).map_or(-1, |i| i as isize)
+}
+
+// ============================================================================
+// Vec<T> reductions
+// ============================================================================
+//
+// Sums widen narrow element types so they don't overflow or lose precision.
+
+/// Sum the elements of Vec<i32> as i64
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_sum_i32(vec: CVec) -> i64 {
+    if vec.ptr.is_null() {
+        return 0;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i32, vec.len);
+    slice.iter().map(|&x| x as i64).sum()
+}
+
+/// Sum the elements of Vec<i64>, wrapping on overflow
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_sum_i64(vec: CVec) -> i64 {
+    if vec.ptr.is_null() {
+        return 0;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i64, vec.len);
+    slice.iter().fold(0i64, |acc, &x| acc.wrapping_add(x))
+}
+
+/// Sum the elements of Vec<f32> as f64
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_sum_f32(vec: CVec) -> f64 {
+    if vec.ptr.is_null() {
+        return 0.0;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f32, vec.len);
+    slice.iter().map(|&x| x as f64).sum()
+}
+
+/// Sum the elements of Vec<f64>
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_sum_f64(vec: CVec) -> f64 {
+    if vec.ptr.is_null() {
+        return 0.0;
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f64, vec.len);
+    slice.iter().sum()
+}
+
+/// Arithmetic mean of Vec<f64>
+/// Returns NaN for an empty vec
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_mean_f64(vec: CVec) -> f64 {
+    if vec.ptr.is_null() || vec.len == 0 {
+        return f64::NAN;
+    }
+    let len = vec.len;
+    rust_vec_sum_f64(vec) / len as f64
+}
+
+/// Result of `rust_vec_min_i32` / `rust_vec_max_i32`
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecElem_i32 {
+    pub value: i32,
+    /// 1 if the vec had an element, 0 if it was empty
+    pub present: u8,
+}
+
+/// Smallest element of Vec<i32> (present = 0 when empty)
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_min_i32(vec: CVec) -> CVecElem_i32 {
+    if vec.ptr.is_null() {
+        return CVecElem_i32 { value: 0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i32, vec.len);
+    match slice.iter().copied().min() {
+        Some(value) => CVecElem_i32 { value, present: 1 },
+        None => CVecElem_i32 { value: 0, present: 0 },
+    }
+}
+
+/// Largest element of Vec<i32> (present = 0 when empty)
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_max_i32(vec: CVec) -> CVecElem_i32 {
+    if vec.ptr.is_null() {
+        return CVecElem_i32 { value: 0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i32, vec.len);
+    match slice.iter().copied().max() {
+        Some(value) => CVecElem_i32 { value, present: 1 },
+        None => CVecElem_i32 { value: 0, present: 0 },
+    }
+}
+
+/// Result of `rust_vec_min_i64` / `rust_vec_max_i64`
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecElem_i64 {
+    pub value: i64,
+    /// 1 if the vec had an element, 0 if it was empty
+    pub present: u8,
+}
+
+/// Smallest element of Vec<i64> (present = 0 when empty)
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_min_i64(vec: CVec) -> CVecElem_i64 {
+    if vec.ptr.is_null() {
+        return CVecElem_i64 { value: 0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i64, vec.len);
+    match slice.iter().copied().min() {
+        Some(value) => CVecElem_i64 { value, present: 1 },
+        None => CVecElem_i64 { value: 0, present: 0 },
+    }
+}
+
+/// Largest element of Vec<i64> (present = 0 when empty)
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_max_i64(vec: CVec) -> CVecElem_i64 {
+    if vec.ptr.is_null() {
+        return CVecElem_i64 { value: 0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const i64, vec.len);
+    match slice.iter().copied().max() {
+        Some(value) => CVecElem_i64 { value, present: 1 },
+        None => CVecElem_i64 { value: 0, present: 0 },
+    }
+}
+
+/// Result of `rust_vec_min_f32` / `rust_vec_max_f32`
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecElem_f32 {
+    pub value: f32,
+    /// 1 if the vec had an element, 0 if it was empty
+    pub present: u8,
+}
+
+/// Smallest element of Vec<f32> (present = 0 when empty)
+/// Uses `total_cmp` order: negative NaN is smallest, positive NaN is largest
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_min_f32(vec: CVec) -> CVecElem_f32 {
+    if vec.ptr.is_null() {
+        return CVecElem_f32 { value: 0.0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f32, vec.len);
+    match slice.iter().copied().min_by(|a, b| a.total_cmp(b)) {
+        Some(value) => CVecElem_f32 { value, present: 1 },
+        None => CVecElem_f32 { value: 0.0, present: 0 },
+    }
+}
+
+/// Largest element of Vec<f32> (present = 0 when empty)
+/// Uses `total_cmp` order: negative NaN is smallest, positive NaN is largest
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_max_f32(vec: CVec) -> CVecElem_f32 {
+    if vec.ptr.is_null() {
+        return CVecElem_f32 { value: 0.0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f32, vec.len);
+    match slice.iter().copied().max_by(|a, b| a.total_cmp(b)) {
+        Some(value) => CVecElem_f32 { value, present: 1 },
+        None => CVecElem_f32 { value: 0.0, present: 0 },
+    }
+}
+
+/// Result of `rust_vec_min_f64` / `rust_vec_max_f64`
+#[repr(C)]
+#[allow(non_camel_case_types)]
+pub struct CVecElem_f64 {
+    pub value: f64,
+    /// 1 if the vec had an element, 0 if it was empty
+    pub present: u8,
+}
+
+/// Smallest element of Vec<f64> (present = 0 when empty)
+/// Uses `total_cmp` order: negative NaN is smallest, positive NaN is largest
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_min_f64(vec: CVec) -> CVecElem_f64 {
+    if vec.ptr.is_null() {
+        return CVecElem_f64 { value: 0.0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f64, vec.len);
+    match slice.iter().copied().min_by(|a, b| a.total_cmp(b)) {
+        Some(value) => CVecElem_f64 { value, present: 1 },
+        None => CVecElem_f64 { value: 0.0, present: 0 },
+    }
+}
+
+/// Largest element of Vec<f64> (present = 0 when empty)
+/// Uses `total_cmp` order: negative NaN is smallest, positive NaN is largest
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_max_f64(vec: CVec) -> CVecElem_f64 {
+    if vec.ptr.is_null() {
+        return CVecElem_f64 { value: 0.0, present: 0 };
+    }
+    let slice = std::slice::from_raw_parts(vec.ptr as *const f64, vec.len);
+    match slice.iter().copied().max_by(|a, b| a.total_cmp(b)) {
+        Some(value) => CVecElem_f64 { value, present: 1 },
+        None => CVecElem_f64 { value: 0.0, present: 0 },
+    }
+}
+
+// ============================================================================
+// Vec<T> in-place cleanup
+// ============================================================================
+//
+// Both operations keep the buffer and capacity; the input CVec is consumed and the
+// returned one must be used (and dropped) instead.
+
+/// Sort Vec<i32> and remove duplicate values
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_dedup_i32(vec: CVec) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap);
+    v.sort_unstable();
+    v.dedup();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Sort Vec<i64> and remove duplicate values
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_dedup_i64(vec: CVec) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap);
+    v.sort_unstable();
+    v.dedup();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Sort Vec<f32> and remove duplicate values
+/// NaN values are never equal, so each NaN is kept
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_dedup_f32(vec: CVec) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap);
+    v.sort_unstable_by(f32::total_cmp);
+    v.dedup();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Sort Vec<f64> and remove duplicate values
+/// NaN values are never equal, so each NaN is kept
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_dedup_f64(vec: CVec) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap);
+    v.sort_unstable_by(f64::total_cmp);
+    v.dedup();
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Remove every element, keeping the allocated capacity
+/// Works for any of the primitive element types, which need no drop
+#[no_mangle]
+pub extern "C" fn rust_vec_clear(vec: CVec) -> CVec {
+    CVec { len: 0, ..vec }
+}
+
+// ============================================================================
+// Vec<T> resizing
+// ============================================================================
+//
+// The input CVec is consumed and the returned one must be used (and dropped) instead.
+// Shrinking keeps the capacity; growing may reallocate.
+
+/// Shorten Vec<i32> to at most `new_len` elements
+/// A `new_len` at or past the current length leaves the vec unchanged
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_truncate_i32(vec: CVec, new_len: usize) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap);
+    v.truncate(new_len);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Resize Vec<i32> to `new_len`, appending copies of `fill` when growing
+/// A null vec is treated as empty
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers, or have a null `ptr`
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_resize_i32(vec: CVec, new_len: usize, fill: i32) -> CVec {
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap)
+    };
+    v.resize(new_len, fill);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Shorten Vec<i64> to at most `new_len` elements
+/// A `new_len` at or past the current length leaves the vec unchanged
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_truncate_i64(vec: CVec, new_len: usize) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap);
+    v.truncate(new_len);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Resize Vec<i64> to `new_len`, appending copies of `fill` when growing
+/// A null vec is treated as empty
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers, or have a null `ptr`
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_resize_i64(vec: CVec, new_len: usize, fill: i64) -> CVec {
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap)
+    };
+    v.resize(new_len, fill);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Shorten Vec<f32> to at most `new_len` elements
+/// A `new_len` at or past the current length leaves the vec unchanged
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_truncate_f32(vec: CVec, new_len: usize) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap);
+    v.truncate(new_len);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Resize Vec<f32> to `new_len`, appending copies of `fill` when growing
+/// A null vec is treated as empty
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers, or have a null `ptr`
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_resize_f32(vec: CVec, new_len: usize, fill: f32) -> CVec {
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap)
+    };
+    v.resize(new_len, fill);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Shorten Vec<f64> to at most `new_len` elements
+/// A `new_len` at or past the current length leaves the vec unchanged
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_truncate_f64(vec: CVec, new_len: usize) -> CVec {
+    if vec.ptr.is_null() {
+        return vec;
+    }
+    let mut v = Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap);
+    v.truncate(new_len);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+/// Resize Vec<f64> to `new_len`, appending copies of `fill` when growing
+/// A null vec is treated as empty
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers, or have a null `ptr`
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_resize_f64(vec: CVec, new_len: usize, fill: f64) -> CVec {
+    let mut v = if vec.ptr.is_null() {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap)
+    };
+    v.resize(new_len, fill);
+    let len = v.len();
+    let cap = v.capacity();
+    let ptr = v.as_ptr() as *mut c_void;
+    std::mem::forget(v);
+    CVec { ptr, len, cap }
+}
+
+// ============================================================================
+// Vec<T> comparison
+// ============================================================================
+//
+// Both vectors are only borrowed: the caller still owns and drops them.
+
+/// Check whether two Vec<i32> have the same length and elements
+/// # Safety
+/// `a` and `b` must be CVecs of i32 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_equal_i32(a: CVec, b: CVec) -> bool {
+    if a.len != b.len {
+        return false;
+    }
+    if a.len == 0 {
+        return true;
+    }
+    let a = std::slice::from_raw_parts(a.ptr as *const i32, a.len);
+    let b = std::slice::from_raw_parts(b.ptr as *const i32, b.len);
+    a == b
+}
+
+/// Check whether two Vec<i64> have the same length and elements
+/// # Safety
+/// `a` and `b` must be CVecs of i64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_equal_i64(a: CVec, b: CVec) -> bool {
+    if a.len != b.len {
+        return false;
+    }
+    if a.len == 0 {
+        return true;
+    }
+    let a = std::slice::from_raw_parts(a.ptr as *const i64, a.len);
+    let b = std::slice::from_raw_parts(b.ptr as *const i64, b.len);
+    a == b
+}
+
+/// Check whether two Vec<f64> have the same length and elements within `eps`
+/// NaN elements never compare equal
+/// # Safety
+/// `a` and `b` must be CVecs of f64 created by these helpers
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_approx_equal_f64(a: CVec, b: CVec, eps: f64) -> bool {
+    if a.len != b.len {
+        return false;
+    }
+    if a.len == 0 {
+        return true;
+    }
+    let a = std::slice::from_raw_parts(a.ptr as *const f64, a.len);
+    let b = std::slice::from_raw_parts(b.ptr as *const f64, b.len);
+    a.iter().zip(b).all(|(x, y)| (x - y).abs() <= eps)
+}
+
+// ============================================================================
+// Arc<[f64]> helpers (shared read-only buffers)
+// ============================================================================
+//
+// `Arc<[f64]>` is a fat pointer, so handles are a `Box<Arc<[f64]>>` cast to a thin
+// `*const c_void`. Cloning a handle boxes a new `Arc` sharing the same buffer.
+
+/// Consume a Vec<f64> into a shared Arc<[f64]> handle
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_into_arc_f64(vec: CVec) -> *const c_void {
+    let data: Arc<[f64]> = if vec.ptr.is_null() || vec.cap == 0 {
+        Arc::from(Vec::<f64>::new())
+    } else {
+        Arc::from(Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap))
+    };
+    Box::into_raw(Box::new(data)) as *const c_void
+}
+
+/// Get the data pointer of an Arc<[f64]> handle (valid while the handle is alive)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_f64`/`rust_arc_slice_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_slice_ptr_f64(arc: *const c_void) -> *const f64 {
+    if arc.is_null() {
+        return std::ptr::null();
+    }
+    let data = &*(arc as *const Arc<[f64]>);
+    data.as_ptr()
+}
+
+/// Get the length of an Arc<[f64]> handle
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_f64`/`rust_arc_slice_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_slice_len_f64(arc: *const c_void) -> usize {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Arc<[f64]>);
+    data.len()
+}
+
+/// Clone an Arc<[f64]> handle (increment reference count, no data copy)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_f64`/`rust_arc_slice_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_slice_clone_f64(arc: *const c_void) -> *const c_void {
+    if arc.is_null() {
+        return std::ptr::null();
+    }
+    let cloned = Arc::clone(&*(arc as *const Arc<[f64]>));
+    Box::into_raw(Box::new(cloned)) as *const c_void
+}
+
+/// Drop an Arc<[f64]> handle (decrement reference count)
+/// # Safety
+/// `arc` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_slice_drop_f64(arc: *const c_void) {
+    if !arc.is_null() {
+        let _ = Box::from_raw(arc as *mut Arc<[f64]>);
+    }
+}
+
+// ============================================================================
+// Arc<Vec<u8>> helpers (shared byte blobs)
+// ============================================================================
+//
+// `Arc<Vec<u8>>` has a sized inner value, so handles are thin `Arc::into_raw` pointers
+// like the primitive Arc helpers above and follow the same `from_raw`/`forget` discipline.
+
+/// Create an Arc<Vec<u8>> holding a copy of `len` bytes from `data`
+/// # Safety
+/// `data` must point to `len` readable bytes (it may be null when `len` is 0)
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_new_bytes(data: *const u8, len: usize) -> *mut c_void {
+    let bytes = if data.is_null() || len == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(data, len).to_vec()
+    };
+    Arc::into_raw(Arc::new(bytes)) as *mut c_void
+}
+
+/// Get the length of an Arc<Vec<u8>>
+/// # Safety
+/// `ptr` must be null or a live handle from `rust_arc_new_bytes`/`rust_arc_clone_bytes`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_bytes_len(ptr: *mut c_void) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    let bytes = &*(ptr as *const Vec<u8>);
+    bytes.len()
+}
+
+/// Get the data pointer of an Arc<Vec<u8>> (valid while any clone is alive)
+/// # Safety
+/// `ptr` must be null or a live handle from `rust_arc_new_bytes`/`rust_arc_clone_bytes`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_bytes_ptr(ptr: *mut c_void) -> *const u8 {
+    if ptr.is_null() {
+        return std::ptr::null();
+    }
+    let bytes = &*(ptr as *const Vec<u8>);
+    bytes.as_ptr()
+}
+
+/// Clone an Arc<Vec<u8>> (increment reference count, no data copy)
+/// # Safety
+/// `ptr` must be null or a live handle from `rust_arc_new_bytes`/`rust_arc_clone_bytes`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_clone_bytes(ptr: *mut c_void) -> *mut c_void {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    let arc = Arc::from_raw(ptr as *const Vec<u8>);
+    let cloned = Arc::clone(&arc);
+    std::mem::forget(arc);  // Keep original reference alive
+    Arc::into_raw(cloned) as *mut c_void
+}
+
+/// Drop an Arc<Vec<u8>> (decrement reference count)
+/// # Safety
+/// `ptr` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_drop_bytes(ptr: *mut c_void) {
+    if !ptr.is_null() {
+        let _ = Arc::from_raw(ptr as *const Vec<u8>);
+    }
+}
+
+// ============================================================================
+// Arc<Vec<T>> helpers (shared read-only vectors)
+// ============================================================================
+//
+// Consuming a CVec moves its buffer into an `Arc<Vec<T>>` without copying. Handles are
+// thin `Arc::into_raw` pointers; cloning only bumps the reference count. These are not
+// interchangeable with the `Arc<[f64]>` handles of `rust_vec_into_arc_f64`.
+
+/// Consume a Vec<i32> into a shared Arc<Vec<i32>> handle (no data copy)
+/// # Safety
+/// `vec` must be a CVec of i32 created by these helpers; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_into_arc_vec_i32(vec: CVec) -> *mut c_void {
+    let data: Vec<i32> = if vec.ptr.is_null() || vec.cap == 0 {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i32, vec.len, vec.cap)
+    };
+    Arc::into_raw(Arc::new(data)) as *mut c_void
+}
+
+/// Get an element from an Arc<Vec<i32>> by index
+/// Returns 0 if index is out of bounds (caller should check bounds first)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i32`/`rust_arc_vec_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_get_i32(arc: *mut c_void, index: usize) -> i32 {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<i32>);
+    data.get(index).copied().unwrap_or(0)
+}
+
+/// Get the length of an Arc<Vec<i32>>
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i32`/`rust_arc_vec_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_len_i32(arc: *mut c_void) -> usize {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<i32>);
+    data.len()
+}
+
+/// Clone an Arc<Vec<i32>> (increment reference count, no data copy)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i32`/`rust_arc_vec_clone_i32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_clone_i32(arc: *mut c_void) -> *mut c_void {
+    if arc.is_null() {
+        return std::ptr::null_mut();
+    }
+    let original = Arc::from_raw(arc as *const Vec<i32>);
+    let cloned = Arc::clone(&original);
+    std::mem::forget(original);  // Keep original reference alive
+    Arc::into_raw(cloned) as *mut c_void
+}
+
+/// Drop an Arc<Vec<i32>> (decrement reference count)
+/// # Safety
+/// `arc` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_drop_i32(arc: *mut c_void) {
+    if !arc.is_null() {
+        let _ = Arc::from_raw(arc as *const Vec<i32>);
+    }
+}
+
+/// Consume a Vec<i64> into a shared Arc<Vec<i64>> handle (no data copy)
+/// # Safety
+/// `vec` must be a CVec of i64 created by these helpers; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_into_arc_vec_i64(vec: CVec) -> *mut c_void {
+    let data: Vec<i64> = if vec.ptr.is_null() || vec.cap == 0 {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut i64, vec.len, vec.cap)
+    };
+    Arc::into_raw(Arc::new(data)) as *mut c_void
+}
+
+/// Get an element from an Arc<Vec<i64>> by index
+/// Returns 0 if index is out of bounds (caller should check bounds first)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i64`/`rust_arc_vec_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_get_i64(arc: *mut c_void, index: usize) -> i64 {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<i64>);
+    data.get(index).copied().unwrap_or(0)
+}
+
+/// Get the length of an Arc<Vec<i64>>
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i64`/`rust_arc_vec_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_len_i64(arc: *mut c_void) -> usize {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<i64>);
+    data.len()
+}
+
+/// Clone an Arc<Vec<i64>> (increment reference count, no data copy)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_i64`/`rust_arc_vec_clone_i64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_clone_i64(arc: *mut c_void) -> *mut c_void {
+    if arc.is_null() {
+        return std::ptr::null_mut();
+    }
+    let original = Arc::from_raw(arc as *const Vec<i64>);
+    let cloned = Arc::clone(&original);
+    std::mem::forget(original);  // Keep original reference alive
+    Arc::into_raw(cloned) as *mut c_void
+}
+
+/// Drop an Arc<Vec<i64>> (decrement reference count)
+/// # Safety
+/// `arc` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_drop_i64(arc: *mut c_void) {
+    if !arc.is_null() {
+        let _ = Arc::from_raw(arc as *const Vec<i64>);
+    }
+}
+
+/// Consume a Vec<f32> into a shared Arc<Vec<f32>> handle (no data copy)
+/// # Safety
+/// `vec` must be a CVec of f32 created by these helpers; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_into_arc_vec_f32(vec: CVec) -> *mut c_void {
+    let data: Vec<f32> = if vec.ptr.is_null() || vec.cap == 0 {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f32, vec.len, vec.cap)
+    };
+    Arc::into_raw(Arc::new(data)) as *mut c_void
+}
+
+/// Get an element from an Arc<Vec<f32>> by index
+/// Returns 0 if index is out of bounds (caller should check bounds first)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f32`/`rust_arc_vec_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_get_f32(arc: *mut c_void, index: usize) -> f32 {
+    if arc.is_null() {
+        return 0.0;
+    }
+    let data = &*(arc as *const Vec<f32>);
+    data.get(index).copied().unwrap_or(0.0)
+}
+
+/// Get the length of an Arc<Vec<f32>>
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f32`/`rust_arc_vec_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_len_f32(arc: *mut c_void) -> usize {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<f32>);
+    data.len()
+}
+
+/// Clone an Arc<Vec<f32>> (increment reference count, no data copy)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f32`/`rust_arc_vec_clone_f32`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_clone_f32(arc: *mut c_void) -> *mut c_void {
+    if arc.is_null() {
+        return std::ptr::null_mut();
+    }
+    let original = Arc::from_raw(arc as *const Vec<f32>);
+    let cloned = Arc::clone(&original);
+    std::mem::forget(original);  // Keep original reference alive
+    Arc::into_raw(cloned) as *mut c_void
+}
+
+/// Drop an Arc<Vec<f32>> (decrement reference count)
+/// # Safety
+/// `arc` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_drop_f32(arc: *mut c_void) {
+    if !arc.is_null() {
+        let _ = Arc::from_raw(arc as *const Vec<f32>);
+    }
+}
+
+/// Consume a Vec<f64> into a shared Arc<Vec<f64>> handle (no data copy)
+/// # Safety
+/// `vec` must be a CVec of f64 created by these helpers; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_vec_into_arc_vec_f64(vec: CVec) -> *mut c_void {
+    let data: Vec<f64> = if vec.ptr.is_null() || vec.cap == 0 {
+        Vec::new()
+    } else {
+        Vec::from_raw_parts(vec.ptr as *mut f64, vec.len, vec.cap)
+    };
+    Arc::into_raw(Arc::new(data)) as *mut c_void
+}
+
+/// Get an element from an Arc<Vec<f64>> by index
+/// Returns 0 if index is out of bounds (caller should check bounds first)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f64`/`rust_arc_vec_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_get_f64(arc: *mut c_void, index: usize) -> f64 {
+    if arc.is_null() {
+        return 0.0;
+    }
+    let data = &*(arc as *const Vec<f64>);
+    data.get(index).copied().unwrap_or(0.0)
+}
+
+/// Get the length of an Arc<Vec<f64>>
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f64`/`rust_arc_vec_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_len_f64(arc: *mut c_void) -> usize {
+    if arc.is_null() {
+        return 0;
+    }
+    let data = &*(arc as *const Vec<f64>);
+    data.len()
+}
+
+/// Clone an Arc<Vec<f64>> (increment reference count, no data copy)
+/// # Safety
+/// `arc` must be null or a live handle from `rust_vec_into_arc_vec_f64`/`rust_arc_vec_clone_f64`
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_clone_f64(arc: *mut c_void) -> *mut c_void {
+    if arc.is_null() {
+        return std::ptr::null_mut();
+    }
+    let original = Arc::from_raw(arc as *const Vec<f64>);
+    let cloned = Arc::clone(&original);
+    std::mem::forget(original);  // Keep original reference alive
+    Arc::into_raw(cloned) as *mut c_void
+}
+
+/// Drop an Arc<Vec<f64>> (decrement reference count)
+/// # Safety
+/// `arc` must be null or a live handle; it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_arc_vec_drop_f64(arc: *mut c_void) {
+    if !arc.is_null() {
+        let _ = Arc::from_raw(arc as *const Vec<f64>);
+    }
+}
+
+// ============================================================================
+// C string helpers
+// ============================================================================
+//
+// Strings these helpers hand to Julia as `*mut c_char` are `CString::into_raw` pointers
+// and must be released with `rust_string_free`, never with libc `free`. Strings returned
+// by `#[julia]` functions come from another library and its allocator; free those with
+// the function generated next to them (e.g. `<Struct>_debug_string_free`).
+
+/// Get the length of a NUL-terminated C string in bytes (excluding the NUL)
+/// Returns 0 for a null pointer
+/// # Safety
+/// `ptr` must be null or point to a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rust_cstr_len(ptr: *const c_char) -> usize {
+    if ptr.is_null() {
+        return 0;
+    }
+    CStr::from_ptr(ptr).to_bytes().len()
+}
+
+/// Copy a NUL-terminated C string into a new Rust-owned C string
+/// Returns null for a null pointer; free the copy with `rust_string_free`
+/// # Safety
+/// `ptr` must be null or point to a valid NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn rust_cstr_clone(ptr: *const c_char) -> *mut c_char {
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+    CStr::from_ptr(ptr).to_owned().into_raw()
+}
+
+/// Free a C string allocated by Rust (`CString::into_raw`)
+/// # Safety
+/// `ptr` must be null or a string returned by these helpers or `#[julia]` wrappers;
+/// it must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn rust_string_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+// ============================================================================
+// Panic capture
+// ============================================================================
+//
+// The hook records the message of the last panic on each thread, so Julia can
+// report why a call failed even when the panic was caught with `catch_unwind`.
+
+thread_local! {
+    static LAST_PANIC_MESSAGE: std::cell::RefCell<Option<CString>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Install a panic hook recording each panic's message for `rust_take_last_panic_message`
+/// The previous hook still runs, so panics are reported as before. Installing twice is a no-op.
+#[no_mangle]
+pub extern "C" fn rust_install_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            let text = match info.location() {
+                Some(location) => format!("{} at {}:{}", message, location.file(), location.line()),
+                None => message.to_string(),
+            };
+            // Interior NUL bytes cannot be represented in a C string
+            let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+            LAST_PANIC_MESSAGE.with(|last| *last.borrow_mut() = Some(text));
+            previous(info);
+        }));
+    });
+}
+
+/// Take the message of the last panic on the current thread
+/// Returns null if there was none since the last call; free it with `rust_string_free`
+#[no_mangle]
+pub extern "C" fn rust_take_last_panic_message() -> *mut c_char {
+    LAST_PANIC_MESSAGE
+        .with(|last| last.borrow_mut().take())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cvec_copy(vec: &CVec) -> CVec {
+        CVec {
+            ptr: vec.ptr,
+            len: vec.len,
+            cap: vec.cap,
+        }
+    }
+
+    #[test]
+    fn vec_as_ptr_reads_same_elements_as_get() {
+        let data = [3, 1, 4, 1, 5];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let ptr = rust_vec_as_ptr_i32(cvec_copy(&vec));
+            let view = std::slice::from_raw_parts(ptr, vec.len);
+            for (i, value) in view.iter().enumerate() {
+                assert_eq!(*value, rust_vec_get_i32(cvec_copy(&vec), i));
+            }
+            rust_vec_drop_i32(vec);
+
+            // An empty Vec yields a dangling, non-null pointer: a valid zero-length view
+            let empty = rust_vec_new_i32();
+            let ptr = rust_vec_as_ptr_i32(cvec_copy(&empty));
+            assert!(!ptr.is_null());
+            assert!(std::slice::from_raw_parts(ptr, empty.len).is_empty());
+            rust_vec_drop_i32(empty);
+        }
+    }
+    #[test]
+    fn arc_slice_clone_shares_data() {
+        let data = [1.5, 2.5, 3.5];
+        unsafe {
+            let vec = rust_vec_new_from_array_f64(data.as_ptr(), data.len());
+            let arc = rust_vec_into_arc_f64(vec);
+            let clone = rust_arc_slice_clone_f64(arc);
+
+            assert_eq!(rust_arc_slice_len_f64(arc), 3);
+            assert_eq!(rust_arc_slice_len_f64(clone), 3);
+            assert_eq!(rust_arc_slice_ptr_f64(arc), rust_arc_slice_ptr_f64(clone));
+
+            rust_arc_slice_drop_f64(arc);
+            let view = std::slice::from_raw_parts(rust_arc_slice_ptr_f64(clone), 3);
+            assert_eq!(view, &data);
+            rust_arc_slice_drop_f64(clone);
+        }
+    }
+
+    #[test]
+    fn arc_bytes_shared_across_threads() {
+        let data = b"shared blob";
+        unsafe {
+            let arc = rust_arc_new_bytes(data.as_ptr(), data.len());
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let clone = rust_arc_clone_bytes(arc) as usize;
+                    std::thread::spawn(move || {
+                        let clone = clone as *mut c_void;
+                        let view = std::slice::from_raw_parts(
+                            rust_arc_bytes_ptr(clone),
+                            rust_arc_bytes_len(clone),
+                        );
+                        let copy = view.to_vec();
+                        rust_arc_drop_bytes(clone);
+                        copy
+                    })
+                })
+                .collect();
+            for handle in handles {
+                assert_eq!(handle.join().unwrap(), data.to_vec());
+            }
+            // All thread clones were dropped, leaving only the original handle
+            let original = std::mem::ManuallyDrop::new(Arc::from_raw(arc as *const Vec<u8>));
+            assert_eq!(Arc::strong_count(&original), 1);
+            rust_arc_drop_bytes(arc);
+
+            let empty = rust_arc_new_bytes(std::ptr::null(), 0);
+            assert_eq!(rust_arc_bytes_len(empty), 0);
+            rust_arc_drop_bytes(empty);
+        }
+    }
+
+    #[test]
+    fn vec_extend_from_array_appends_in_bulk() {
+        let head = [7, 8];
+        let tail: Vec<i32> = (0..1000).collect();
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(head.as_ptr(), head.len());
+            let vec = rust_vec_extend_from_array_i32(vec, tail.as_ptr(), tail.len());
+            assert_eq!(vec.len, 1002);
+            let view = std::slice::from_raw_parts(vec.ptr as *const i32, vec.len);
+            assert_eq!(&view[..2], &head);
+            assert_eq!(&view[2..], tail.as_slice());
+
+            let vec = rust_vec_extend_from_array_i32(vec, std::ptr::null(), 0);
+            assert_eq!(vec.len, 1002);
+            rust_vec_drop_i32(vec);
+        }
+    }
+
+    #[test]
+    fn arc_vec_clone_reads_same_data() {
+        let data = [5, -6, 7, -8];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let arc = rust_vec_into_arc_vec_i32(vec);
+            let clone = rust_arc_vec_clone_i32(arc);
+            assert_eq!(rust_arc_vec_len_i32(arc), data.len());
+            assert_eq!(rust_arc_vec_len_i32(clone), data.len());
+            for (i, value) in data.iter().enumerate() {
+                assert_eq!(rust_arc_vec_get_i32(arc, i), *value);
+                assert_eq!(rust_arc_vec_get_i32(clone, i), *value);
+            }
+            assert_eq!(rust_arc_vec_get_i32(clone, data.len()), 0);
+            rust_arc_vec_drop_i32(arc);
+            assert_eq!(rust_arc_vec_get_i32(clone, 3), -8);
+            rust_arc_vec_drop_i32(clone);
+        }
+    }
+
+    #[test]
+    fn arc_vec_f64_matches_other_element_types() {
+        let data = [0.5, -1.25];
+        unsafe {
+            let vec = rust_vec_new_from_array_f64(data.as_ptr(), data.len());
+            let arc = rust_vec_into_arc_vec_f64(vec);
+            let clone = rust_arc_vec_clone_f64(arc);
+            rust_arc_vec_drop_f64(arc);
+            assert_eq!(rust_arc_vec_len_f64(clone), 2);
+            assert_eq!(rust_arc_vec_get_f64(clone, 1), -1.25);
+            assert_eq!(rust_arc_vec_get_f64(clone, 2), 0.0);
+            rust_arc_vec_drop_f64(clone);
+        }
+    }
+
+    #[test]
+    fn cstr_len_clone_free_round_trip() {
+        let original = CString::new("hello, julia").unwrap();
+        unsafe {
+            assert_eq!(rust_cstr_len(original.as_ptr()), 12);
+            let copy = rust_cstr_clone(original.as_ptr());
+            assert_ne!(copy as *const c_char, original.as_ptr());
+            assert_eq!(CStr::from_ptr(copy), original.as_c_str());
+            assert_eq!(rust_cstr_len(copy), 12);
+            rust_string_free(copy);
+
+            assert_eq!(rust_cstr_len(std::ptr::null()), 0);
+            assert!(rust_cstr_clone(std::ptr::null()).is_null());
+            rust_string_free(std::ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn vec_insert_middle_and_remove_front() {
+        let data = [1, 2, 4];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let vec = rust_vec_insert_i32(vec, 2, 3);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const i32, vec.len), &[1, 2, 3, 4]);
+
+            let vec = rust_vec_insert_i32(vec, 10, 99);
+            assert_eq!(vec.len, 4);
+
+            let popped = rust_vec_remove_i32(vec, 0);
+            assert_eq!(popped.ok, 1);
+            assert_eq!(popped.value, 1);
+            let vec = popped.vec;
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const i32, vec.len), &[2, 3, 4]);
+
+            let popped = rust_vec_remove_i32(vec, 3);
+            assert_eq!(popped.ok, 0);
+            assert_eq!(popped.vec.len, 3);
+            rust_vec_drop_i32(popped.vec);
+        }
+    }
+
+    #[test]
+    fn vec_scale_and_add_scalar_in_place() {
+        let data = [1.0, -2.0, 0.5];
+        unsafe {
+            let vec = rust_vec_new_from_array_f64(data.as_ptr(), data.len());
+            rust_vec_scale_f64(cvec_copy(&vec), 4.0);
+            rust_vec_add_scalar_f64(cvec_copy(&vec), 1.0);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const f64, vec.len), &[5.0, -7.0, 3.0]);
+            rust_vec_drop_f64(vec);
+
+            let empty = rust_vec_new_from_array_f64(std::ptr::null(), 0);
+            rust_vec_scale_f64(cvec_copy(&empty), 2.0);
+            rust_vec_add_scalar_f64(cvec_copy(&empty), 2.0);
+            assert_eq!(empty.len, 0);
+            rust_vec_drop_f64(empty);
+        }
+    }
+
+    #[test]
+    fn rc_counts_follow_clone_and_drop() {
+        unsafe {
+            let rc = rust_rc_new_i32(42);
+            assert_eq!(rust_rc_strong_count_i32(rc), 1);
+            let clone = rust_rc_clone_i32(rc);
+            assert_eq!(rust_rc_strong_count_i32(rc), 2);
+            assert_eq!(rust_rc_strong_count_i32(clone), 2);
+            assert_eq!(rust_rc_weak_count_i32(rc), 0);
+
+            let weak = Rc::downgrade(&Rc::from_raw(clone as *const i32));
+            assert_eq!(rust_rc_strong_count_i32(rc), 1);
+            assert_eq!(rust_rc_weak_count_i32(rc), 1);
+            drop(weak);
+
+            assert_eq!(rust_rc_strong_count_i32(std::ptr::null_mut()), 0);
+            assert_eq!(rust_rc_weak_count_i32(std::ptr::null_mut()), 0);
+            rust_rc_drop_i32(rc);
+        }
+    }
+
+    #[test]
+    fn arc_ptr_eq_tells_clones_from_copies() {
+        unsafe {
+            let arc = rust_arc_new_i32(7);
+            let clone = rust_arc_clone_i32(arc);
+            let other = rust_arc_new_i32(7);
+            assert!(rust_arc_ptr_eq_i32(arc, clone));
+            assert!(rust_arc_ptr_eq_i32(arc, arc));
+            assert!(!rust_arc_ptr_eq_i32(arc, other));
+            assert!(!rust_arc_ptr_eq_i32(arc, std::ptr::null_mut()));
+
+            // The comparison must not change the reference counts
+            let original = Arc::from_raw(arc as *const i32);
+            assert_eq!(Arc::strong_count(&original), 2);
+            drop(original);
+            rust_arc_drop_i32(clone);
+            rust_arc_drop_i32(other);
+        }
+    }
+
+    #[test]
+    fn vec_contains_and_index_of_find_first_match() {
+        let data = [4, 8, 15, 8];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            assert!(rust_vec_contains_i32(cvec_copy(&vec), 15));
+            assert_eq!(rust_vec_index_of_i32(cvec_copy(&vec), 8), 1);
+            assert!(!rust_vec_contains_i32(cvec_copy(&vec), 16));
+            assert_eq!(rust_vec_index_of_i32(cvec_copy(&vec), 16), -1);
+            rust_vec_drop_i32(vec);
+
+            let floats = [0.5, f64::NAN];
+            let vec = rust_vec_new_from_array_f64(floats.as_ptr(), floats.len());
+            assert_eq!(rust_vec_index_of_f64(cvec_copy(&vec), 0.5), 0);
+            assert!(!rust_vec_contains_f64(cvec_copy(&vec), f64::NAN));
+            rust_vec_drop_f64(vec);
+
+            let empty = rust_vec_new_from_array_i32(std::ptr::null(), 0);
+            assert_eq!(rust_vec_index_of_i32(empty, 0), -1);
+        }
+    }
+
+    #[test]
+    fn vec_fill_repeats_value() {
+        let vec = rust_vec_fill_f64(5, 1.5);
+        assert_eq!(vec.len, 5);
+        unsafe {
+            for i in 0..5 {
+                assert_eq!(rust_vec_get_f64(cvec_copy(&vec), i), 1.5);
+            }
+            rust_vec_drop_f64(vec);
+
+            let empty = rust_vec_fill_i32(0, 7);
+            assert_eq!(empty.len, 0);
+            rust_vec_drop_i32(empty);
+        }
+    }
+
+    #[test]
+    fn weak_upgrade_fails_after_last_arc_drops() {
+        unsafe {
+            let arc = rust_arc_new_i32(11);
+            let weak = rust_arc_downgrade_i32(arc);
+            assert!(!weak.is_null());
+
+            let upgraded = rust_weak_upgrade_i32(weak);
+            assert!(rust_arc_ptr_eq_i32(arc, upgraded));
+            assert_eq!(*(upgraded as *const i32), 11);
+            rust_arc_drop_i32(upgraded);
+
+            rust_arc_drop_i32(arc);
+            assert!(rust_weak_upgrade_i32(weak).is_null());
+            rust_weak_drop_i32(weak);
+
+            assert!(rust_arc_downgrade_i32(std::ptr::null_mut()).is_null());
+            assert!(rust_weak_upgrade_i32(std::ptr::null_mut()).is_null());
+        }
+    }
+
+    #[test]
+    fn vec_dedup_and_clear_keep_capacity() {
+        let data = [3, 1, 3, 2, 1, 3];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let cap = vec.cap;
+            let vec = rust_vec_dedup_i32(vec);
+            assert_eq!(vec.len, 3);
+            assert_eq!(vec.cap, cap);
+            assert_eq!(rust_vec_get_i32(cvec_copy(&vec), 0), 1);
+            assert_eq!(rust_vec_get_i32(cvec_copy(&vec), 2), 3);
+
+            let vec = rust_vec_clear(vec);
+            assert_eq!(vec.len, 0);
+            assert_eq!(vec.cap, cap);
+            rust_vec_drop_i32(vec);
+
+            let floats = [0.5, -1.0, 0.5];
+            let vec = rust_vec_dedup_f64(rust_vec_new_from_array_f64(floats.as_ptr(), floats.len()));
+            assert_eq!(vec.len, 2);
+            assert_eq!(rust_vec_get_f64(cvec_copy(&vec), 0), -1.0);
+            rust_vec_drop_f64(vec);
+        }
+    }
+
+    #[test]
+    fn vec_sum_and_mean() {
+        let ints = [i32::MAX, i32::MAX, -4];
+        let floats = [1.0, 2.5, 4.0, 0.5];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(ints.as_ptr(), ints.len());
+            assert_eq!(rust_vec_sum_i32(cvec_copy(&vec)), 2 * i32::MAX as i64 - 4);
+            rust_vec_drop_i32(vec);
+
+            let vec = rust_vec_new_from_array_f64(floats.as_ptr(), floats.len());
+            assert_eq!(rust_vec_sum_f64(cvec_copy(&vec)), 8.0);
+            assert_eq!(rust_vec_mean_f64(cvec_copy(&vec)), 2.0);
+            rust_vec_drop_f64(vec);
+
+            let empty = rust_vec_new_from_array_f64(std::ptr::null(), 0);
+            assert_eq!(rust_vec_sum_f64(cvec_copy(&empty)), 0.0);
+            assert!(rust_vec_mean_f64(empty).is_nan());
+        }
+    }
+
+    #[test]
+    fn vec_equal_compares_without_consuming() {
+        let data = [4, 8, 15];
+        let other = [4, 8, 16];
+        unsafe {
+            let a = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let b = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let shorter = rust_vec_new_from_array_i32(data.as_ptr(), 2);
+            let differing = rust_vec_new_from_array_i32(other.as_ptr(), other.len());
+            assert!(rust_vec_equal_i32(cvec_copy(&a), cvec_copy(&b)));
+            assert!(!rust_vec_equal_i32(cvec_copy(&a), cvec_copy(&shorter)));
+            assert!(!rust_vec_equal_i32(cvec_copy(&a), cvec_copy(&differing)));
+            assert_eq!(rust_vec_get_i32(cvec_copy(&a), 2), 15);
+            rust_vec_drop_i32(a);
+            rust_vec_drop_i32(b);
+            rust_vec_drop_i32(shorter);
+            rust_vec_drop_i32(differing);
+
+            let x = [1.0, 2.0, 3.0];
+            let y = [1.0, 2.0 + 1e-12, 3.0];
+            let a = rust_vec_new_from_array_f64(x.as_ptr(), x.len());
+            let b = rust_vec_new_from_array_f64(y.as_ptr(), y.len());
+            let shorter = rust_vec_new_from_array_f64(x.as_ptr(), 2);
+            assert!(rust_vec_approx_equal_f64(cvec_copy(&a), cvec_copy(&b), 1e-9));
+            assert!(!rust_vec_approx_equal_f64(cvec_copy(&a), cvec_copy(&b), 0.0));
+            assert!(!rust_vec_approx_equal_f64(cvec_copy(&a), cvec_copy(&shorter), 1e-9));
+            rust_vec_drop_f64(a);
+            rust_vec_drop_f64(b);
+            rust_vec_drop_f64(shorter);
+        }
+    }
+
+    #[test]
+    fn panic_hook_records_caught_panic_message() {
+        rust_install_panic_hook();
+        rust_install_panic_hook();
+        assert!(rust_take_last_panic_message().is_null());
+
+        let result = std::panic::catch_unwind(|| panic!("bad input: {}", 42));
+        assert!(result.is_err());
+
+        let message = rust_take_last_panic_message();
+        assert!(!message.is_null());
+        unsafe {
+            let text = CStr::from_ptr(message).to_str().unwrap().to_owned();
+            assert!(text.starts_with("bad input: 42 at "));
+            assert!(text.contains("lib.rs"));
+            rust_string_free(message);
+        }
+        assert!(rust_take_last_panic_message().is_null());
+    }
+
+    #[test]
+    fn vec_min_max_report_presence() {
+        let ints = [7, -3, 12, 0];
+        let floats = [2.5, -0.5, 9.0];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(ints.as_ptr(), ints.len());
+            let min = rust_vec_min_i32(cvec_copy(&vec));
+            let max = rust_vec_max_i32(cvec_copy(&vec));
+            assert_eq!((min.present, min.value), (1, -3));
+            assert_eq!((max.present, max.value), (1, 12));
+            rust_vec_drop_i32(vec);
+
+            let vec = rust_vec_new_from_array_f64(floats.as_ptr(), floats.len());
+            assert_eq!(rust_vec_min_f64(cvec_copy(&vec)).value, -0.5);
+            assert_eq!(rust_vec_max_f64(cvec_copy(&vec)).value, 9.0);
+            rust_vec_drop_f64(vec);
+
+            let empty = rust_vec_new_i32();
+            assert_eq!(rust_vec_min_i32(cvec_copy(&empty)).present, 0);
+            assert_eq!(rust_vec_max_i32(cvec_copy(&empty)).present, 0);
+            rust_vec_drop_i32(empty);
+
+            let empty = rust_vec_new_from_array_f64(floats.as_ptr(), 0);
+            assert_eq!(rust_vec_max_f64(cvec_copy(&empty)).present, 0);
+            rust_vec_drop_f64(empty);
+        }
+    }
+
+    #[test]
+    fn vec_min_max_order_nan_by_sign() {
+        let floats = [1.0, -f64::NAN, f64::NEG_INFINITY, f64::NAN];
+        unsafe {
+            let vec = rust_vec_new_from_array_f64(floats.as_ptr(), floats.len());
+            let min = rust_vec_min_f64(cvec_copy(&vec)).value;
+            let max = rust_vec_max_f64(cvec_copy(&vec)).value;
+            assert!(min.is_nan() && min.is_sign_negative());
+            assert!(max.is_nan() && max.is_sign_positive());
+            rust_vec_drop_f64(vec);
+        }
+    }
+
+    #[test]
+    fn bool_vec_round_trips() {
+        let flags = [true, false, true];
+        unsafe {
+            let vec = rust_vec_new_from_array_bool(flags.as_ptr(), flags.len());
+            assert_eq!(vec.len, 3);
+            assert!(rust_vec_get_bool(cvec_copy(&vec), 0));
+            assert!(!rust_vec_get_bool(cvec_copy(&vec), 1));
+            assert!(!rust_vec_get_bool(cvec_copy(&vec), 3));
+
+            assert!(rust_vec_set_bool(cvec_copy(&vec), 1, true));
+            assert!(!rust_vec_set_bool(cvec_copy(&vec), 3, true));
+            let vec = rust_vec_push_bool(vec, false);
+            assert_eq!(vec.len, 4);
+
+            let mut out = [false; 4];
+            assert_eq!(rust_vec_copy_to_array_bool(cvec_copy(&vec), out.as_mut_ptr(), out.len()), 4);
+            assert_eq!(out, [true, true, true, false]);
+            rust_vec_drop_bool(vec);
+
+            // Non-canonical bytes are normalized instead of producing invalid bools
+            let bytes = [0u8, 2, 255];
+            let vec = rust_vec_new_from_array_bool(bytes.as_ptr() as *const bool, bytes.len());
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const u8, vec.len), [0, 1, 1]);
+            rust_vec_drop_bool(vec);
+
+            let vec = rust_vec_push_bool(rust_vec_new_from_array_bool(std::ptr::null(), 0), true);
+            assert!(rust_vec_get_bool(cvec_copy(&vec), 0));
+            rust_vec_drop_bool(vec);
+        }
+    }
+
+    #[test]
+    fn vec_zip_add_and_mul_borrow_inputs() {
+        let x = [1.0, 2.0, 3.0];
+        let y = [0.5, -1.0, 4.0, 10.0];
+        unsafe {
+            let a = rust_vec_new_from_array_f64(x.as_ptr(), x.len());
+            let b = rust_vec_new_from_array_f64(y.as_ptr(), 3);
+            let sum = rust_vec_zip_add_f64(cvec_copy(&a), cvec_copy(&b));
+            let product = rust_vec_zip_mul_f64(cvec_copy(&a), cvec_copy(&b));
+            assert_eq!(std::slice::from_raw_parts(sum.ptr as *const f64, sum.len), [1.5, 1.0, 7.0]);
+            assert_eq!(std::slice::from_raw_parts(product.ptr as *const f64, product.len), [0.5, -2.0, 12.0]);
+            assert_eq!(rust_vec_get_f64(cvec_copy(&a), 2), 3.0);
+            rust_vec_drop_f64(sum);
+            rust_vec_drop_f64(product);
+            rust_vec_drop_f64(b);
+
+            // Mismatched lengths stop at the shorter input
+            let longer = rust_vec_new_from_array_f64(y.as_ptr(), y.len());
+            let sum = rust_vec_zip_add_f64(cvec_copy(&longer), cvec_copy(&a));
+            assert_eq!(sum.len, 3);
+            assert_eq!(rust_vec_get_f64(cvec_copy(&sum), 2), 7.0);
+            rust_vec_drop_f64(sum);
+
+            let empty = rust_vec_new_from_array_f64(std::ptr::null(), 0);
+            let product = rust_vec_zip_mul_f64(cvec_copy(&a), cvec_copy(&empty));
+            assert_eq!(product.len, 0);
+            rust_vec_drop_f64(product);
+            rust_vec_drop_f64(longer);
+            rust_vec_drop_f64(a);
+        }
+    }
+
+    #[test]
+    fn vec_resize_grows_with_fill_and_truncate_shrinks() {
+        let data = [1, 2, 3];
+        unsafe {
+            let vec = rust_vec_new_from_array_i32(data.as_ptr(), data.len());
+            let vec = rust_vec_resize_i32(vec, 5, -7);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const i32, vec.len), [1, 2, 3, -7, -7]);
+
+            let cap = vec.cap;
+            let vec = rust_vec_truncate_i32(vec, 2);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const i32, vec.len), [1, 2]);
+            assert_eq!(vec.cap, cap);
+            let vec = rust_vec_truncate_i32(vec, 10);
+            assert_eq!(vec.len, 2);
+            let vec = rust_vec_resize_i32(vec, 1, 0);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const i32, vec.len), [1]);
+            rust_vec_drop_i32(vec);
+
+            let null = CVec { ptr: std::ptr::null_mut(), len: 0, cap: 0 };
+            let vec = rust_vec_resize_f64(null, 3, 0.5);
+            assert_eq!(std::slice::from_raw_parts(vec.ptr as *const f64, vec.len), [0.5, 0.5, 0.5]);
+            let vec = rust_vec_truncate_f64(vec, 0);
+            assert_eq!(vec.len, 0);
+            rust_vec_drop_f64(vec);
+        }
+    }
+}
@@ -88,6 +88,26 @@ assert p.y == 20.0
 
 print("\n✅ Point class works!\n")
 
+# ============================================================================
+# Test fallible constructor (Result<Self, E> raises in Python)
+# ============================================================================
+
+print("=" * 60)
+print("Testing Account fallible constructor")
+print("=" * 60)
+
+account = m.Account(100)
+print(f"Account(100).balance = {account.balance}")
+assert account.balance == 100
+
+try:
+    m.Account(-5)
+    raise AssertionError("Account(-5) should raise")
+except ValueError as err:
+    print(f"Account(-5) raised ValueError: {err}")
+
+print("\n✅ Account fallible constructor works!\n")
+
 # ============================================================================
 # Summary
 # ============================================================================
@@ -109,4 +129,7 @@ Summary of available Python bindings (all from #[julia_pyo3]):
     - Point.distance_from_origin() -> float
     - Point.translate(dx: float, dy: float)
     - Point.scaled(factor: float) -> Point
+
+  Account class:
+    - Account(balance: int), raises ValueError for a negative balance
 """)
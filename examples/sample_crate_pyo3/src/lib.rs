@@ -83,6 +83,42 @@ impl Point {
     }
 }
 
+// ============================================================================
+// Fallible constructor: null for Julia, an exception for Python
+// ============================================================================
+
+/// Error of `Account::new`
+///
+/// PyO3 requires the error of a `#[new]` returning `Result` to convert into `PyErr`;
+/// the conversion only exists in the Python build, since Julia just gets a null pointer.
+#[derive(Debug)]
+pub struct NegativeBalance(pub i64);
+
+#[cfg(feature = "python")]
+impl From<NegativeBalance> for PyErr {
+    fn from(err: NegativeBalance) -> PyErr {
+        pyo3::exceptions::PyValueError::new_err(format!("negative balance: {}", err.0))
+    }
+}
+
+#[julia_pyo3]
+pub struct Account {
+    pub balance: i64,
+}
+
+/// - Julia: `Account_new(balance) -> *mut Account`, null on `Err`
+/// - Python: `Account(balance)` raises `ValueError` on `Err`
+#[julia_pyo3]
+impl Account {
+    pub fn new(balance: i64) -> Result<Self, NegativeBalance> {
+        if balance < 0 {
+            Err(NegativeBalance(balance))
+        } else {
+            Ok(Account { balance })
+        }
+    }
+}
+
 // ============================================================================
 // Python module definition (only when feature="python")
 // ============================================================================
@@ -93,5 +129,6 @@ fn sample_crate_pyo3(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(add, m)?)?;
     m.add_function(wrap_pyfunction!(fibonacci, m)?)?;
     m.add_class::<Point>()?;
+    m.add_class::<Account>()?;
     Ok(())
 }